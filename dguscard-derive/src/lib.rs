@@ -0,0 +1,99 @@
+//! Derive macros for [`dguscard`](https://docs.rs/dguscard/)
+//!
+//! Use them through the `derive` feature of `dguscard`.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, Data, DeriveInput, Error, Fields,
+    GenericParam, Generics,
+};
+
+/// Derives `dguscard::max_size::MaxSize` as the sum of the field sizes.
+///
+/// Enums are supported as long as all their variants are unit variants, encoded as a `u16` index.
+/// `#[serde(...)]` attributes may change the serialized form, e.g. `with` or `skip`, so they are rejected.
+#[proc_macro_derive(MaxSize)]
+pub fn derive_max_size(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let size = match reject_serde_attrs(&input).and_then(|_| max_size(&input.data)) {
+        Ok(size) => size,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let name = input.ident;
+    let generics = add_bounds(input.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::dguscard::max_size::MaxSize for #name #ty_generics #where_clause {
+            const MAX_SIZE: usize = #size;
+        }
+    }
+    .into()
+}
+
+/// Fails on any `#[serde(...)]` attribute of the type, its variants or its fields.
+fn reject_serde_attrs(input: &DeriveInput) -> syn::Result<()> {
+    let fields = match &input.data {
+        Data::Struct(data) => data.fields.iter().collect(),
+        Data::Enum(data) => data.variants.iter().flat_map(|v| &v.fields).collect(),
+        Data::Union(_) => Vec::new(),
+    };
+    let variants = match &input.data {
+        Data::Enum(data) => data.variants.iter().collect(),
+        _ => Vec::new(),
+    };
+    let mut attrs = input
+        .attrs
+        .iter()
+        .chain(variants.iter().flat_map(|v| &v.attrs))
+        .chain(fields.iter().flat_map(|f| &f.attrs));
+    match attrs.find(|attr| attr.path().is_ident("serde")) {
+        Some(attr) => Err(Error::new(
+            attr.span(),
+            "MaxSize can't be derived with serde attributes, implement it by hand",
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Returns the expression summing up the sizes of the fields.
+fn max_size(data: &Data) -> syn::Result<TokenStream> {
+    match data {
+        Data::Struct(data) => Ok(sum_fields(&data.fields)),
+        Data::Enum(data) => match data
+            .variants
+            .iter()
+            .find(|v| !matches!(v.fields, Fields::Unit))
+        {
+            Some(variant) => Err(Error::new(
+                variant.span(),
+                "MaxSize can only be derived for enums with unit variants",
+            )),
+            None => Ok(quote!(<u16 as ::dguscard::max_size::MaxSize>::MAX_SIZE)),
+        },
+        Data::Union(data) => Err(Error::new(
+            data.union_token.span(),
+            "MaxSize can't be derived for unions",
+        )),
+    }
+}
+
+fn sum_fields(fields: &Fields) -> TokenStream {
+    let sizes = fields.iter().map(|field| {
+        let ty = &field.ty;
+        quote!(<#ty as ::dguscard::max_size::MaxSize>::MAX_SIZE)
+    });
+    quote!(0 #(+ #sizes)*)
+}
+
+/// Adds a `MaxSize` bound to every type parameter.
+fn add_bounds(mut generics: Generics) -> Generics {
+    for param in &mut generics.params {
+        if let GenericParam::Type(param) = param {
+            param
+                .bounds
+                .push(parse_quote!(::dguscard::max_size::MaxSize));
+        }
+    }
+    generics
+}
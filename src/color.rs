@@ -0,0 +1,142 @@
+//! Color types
+
+use serde::{Deserialize, Serialize};
+
+/// RGB565 color
+///
+/// Encoded as a single big-endian word, 5 bits red, 6 bits green and 5 bits blue from MSB to LSB.
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::color::Rgb565;
+///
+/// let orange = Rgb565::from((255, 165, 0));
+/// assert_eq!(orange, Rgb565(0xFD20));
+/// // The conversion is lossy
+/// assert_eq!(<(u8, u8, u8)>::from(orange), (255, 166, 0));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Rgb565(pub u16);
+
+impl Rgb565 {
+    /// Black
+    pub const BLACK: Self = Self(0x0000);
+    /// White
+    pub const WHITE: Self = Self(0xFFFF);
+    /// Red
+    pub const RED: Self = Self(0xF800);
+    /// Green
+    pub const GREEN: Self = Self(0x07E0);
+    /// Blue
+    pub const BLUE: Self = Self(0x001F);
+
+    /// Returns a new color from 8-bit channels, truncating the lower bits.
+    pub const fn from_rgb888(r: u8, g: u8, b: u8) -> Self {
+        Self((r as u16 >> 3) << 11 | (g as u16 >> 2) << 5 | b as u16 >> 3)
+    }
+
+    /// Returns the 8-bit channels, replicating the upper bits into the lower bits.
+    pub const fn to_rgb888(self) -> (u8, u8, u8) {
+        let r = (self.0 >> 11) as u8 & 0x1F;
+        let g = (self.0 >> 5) as u8 & 0x3F;
+        let b = self.0 as u8 & 0x1F;
+        (r << 3 | r >> 2, g << 2 | g >> 4, b << 3 | b >> 2)
+    }
+}
+
+impl From<(u8, u8, u8)> for Rgb565 {
+    fn from((r, g, b): (u8, u8, u8)) -> Self {
+        Self::from_rgb888(r, g, b)
+    }
+}
+
+impl From<Rgb565> for (u8, u8, u8) {
+    fn from(color: Rgb565) -> Self {
+        color.to_rgb888()
+    }
+}
+
+impl From<u16> for Rgb565 {
+    fn from(raw: u16) -> Self {
+        Self(raw)
+    }
+}
+
+impl From<Rgb565> for u16 {
+    fn from(color: Rgb565) -> Self {
+        color.0
+    }
+}
+
+#[cfg(feature = "embedded-graphics")]
+mod embedded_graphics {
+    use super::Rgb565;
+    use embedded_graphics_core::pixelcolor::{self, raw::RawU16, IntoStorage};
+
+    impl From<pixelcolor::Rgb565> for Rgb565 {
+        fn from(color: pixelcolor::Rgb565) -> Self {
+            Self(color.into_storage())
+        }
+    }
+
+    impl From<Rgb565> for pixelcolor::Rgb565 {
+        fn from(color: Rgb565) -> Self {
+            RawU16::new(color.0).into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        command::{Word, Write},
+        request::Request,
+        response::word_data,
+    };
+
+    #[test]
+    fn rgb888() {
+        assert_eq!(Rgb565::WHITE, Rgb565::from((255, 255, 255)));
+        assert_eq!(Rgb565::RED, Rgb565::from((255, 0, 0)));
+        assert_eq!((255, 255, 255), Rgb565::WHITE.into());
+        assert_eq!((0, 255, 0), Rgb565::GREEN.into());
+        assert_eq!((0, 0, 0), Rgb565::BLACK.into());
+    }
+
+    #[test]
+    fn serialize() {
+        let buf = &mut [0u8; 16];
+        let mut request = Request::with_slice(
+            buf,
+            Word {
+                addr: 0x1000,
+                cmd: Write,
+            },
+        )
+        .unwrap();
+        request.push(&Rgb565::RED).unwrap();
+        let frame = request.finalize(false).unwrap();
+        assert_eq!(frame, &[0x5A, 0xA5, 5, 0x82, 0x10, 0x00, 0xF8, 0x00]);
+    }
+
+    #[test]
+    fn deserialize() {
+        let input = [0x5A, 0xA5, 6, 0x83, 0x10, 0x00, 1, 0x07, 0xE0];
+        let (_, mut content) = word_data(&input);
+        assert_eq!(Rgb565::GREEN, content.take().unwrap());
+    }
+
+    #[cfg(feature = "embedded-graphics")]
+    #[test]
+    fn embedded_graphics() {
+        use embedded_graphics_core::pixelcolor::{self, RgbColor};
+        assert_eq!(Rgb565::BLUE, pixelcolor::Rgb565::BLUE.into());
+        assert_eq!(
+            pixelcolor::Rgb565::new(1, 2, 3),
+            pixelcolor::Rgb565::from(Rgb565(1 << 11 | 2 << 5 | 3))
+        );
+    }
+}
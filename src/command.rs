@@ -98,11 +98,11 @@ impl Command for Dword<Read> {
 
 /// Curve command
 ///
-/// Write only
+/// Write only, see [`CurveWrite`][crate::curve::CurveWrite] for building the channel data.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Curve {
-    /// Channel
+    /// Channel bitmask, bit `n` selects the channel `n`
     pub ch: u8,
 }
 impl Sealed for Curve {}
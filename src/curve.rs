@@ -0,0 +1,467 @@
+//! Curve (trend chart) helpers
+
+use crate::{
+    command::{Curve, Read, Word, Write},
+    request::{FrameSource, Frames, Request, Storage, MAX_LEN},
+    response::Response,
+    Error, Result,
+};
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of curve channels
+pub const CHANNELS: usize = 8;
+
+trait Sealed {}
+
+/// Curve sample trait (sealed)
+///
+/// Implemented for [`u16`] and [`i16`], each sample occupies a single word.
+#[allow(private_bounds)]
+pub trait Sample: Serialize + Copy + Sealed + 'static {}
+impl Sealed for u16 {}
+impl Sample for u16 {}
+impl Sealed for i16 {}
+impl Sample for i16 {}
+
+/// Multi-channel curve write builder
+///
+/// Builds [`Curve`] requests, i.e. a channel bitmask followed by the samples of the selected channels interleaved,
+/// e.g. `ch0[0], ch1[0], ch0[1], ch1[1], ...` for the channels 0 and 1.
+/// All channels must have the same number of samples.
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::{curve::CurveWrite, request::Slice};
+///
+/// let temperature = [20u16, 21, 22];
+/// let humidity = [40u16, 41, 42];
+/// let curve = CurveWrite::new()
+///     .channel(0, &temperature)
+///     .unwrap()
+///     .channel(2, &humidity)
+///     .unwrap();
+///
+/// let buf = &mut [0u8; 32];
+/// let frame = curve.request(Slice::new(buf)).unwrap().finalize(false).unwrap();
+/// assert_eq!(
+///     frame,
+///     &[0x5A, 0xA5, 14, 0x84, 0b101, 0, 20, 0, 40, 0, 21, 0, 41, 0, 22, 0, 42]
+/// );
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CurveWrite<'a, T> {
+    channels: [&'a [T]; CHANNELS],
+    mask: u8,
+}
+
+impl<T: Sample> Default for CurveWrite<'_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T: Sample> CurveWrite<'a, T> {
+    /// Returns a new builder without any channels.
+    pub const fn new() -> Self {
+        Self {
+            channels: [&[]; CHANNELS],
+            mask: 0,
+        }
+    }
+
+    /// Selects the channel `ch` with the given samples.
+    ///
+    /// Fails with [`Error::RequestBadParam`] if the channel is out of range,
+    /// or the number of samples differs from the previously selected channels.
+    pub fn channel(mut self, ch: u8, samples: &'a [T]) -> Result<Self> {
+        let idx = ch as usize;
+        if idx >= CHANNELS {
+            return Err(Error::RequestBadParam);
+        }
+        let others = self.mask & !(1 << ch);
+        if others != 0 && samples.len() != self.len() {
+            return Err(Error::RequestBadParam);
+        }
+        self.channels[idx] = samples;
+        self.mask |= 1 << ch;
+        Ok(self)
+    }
+
+    /// Returns the channel bitmask.
+    pub fn mask(&self) -> u8 {
+        self.mask
+    }
+
+    /// Returns the number of samples per channel.
+    pub fn len(&self) -> usize {
+        self.selected().next().map_or(0, |samples| samples.len())
+    }
+
+    /// Returns true if there are no samples.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a new request containing all the samples.
+    pub fn request<S: Storage>(&self, output: S) -> Result<Request<Write, S>> {
+        self.request_range(output, 0, self.len())
+    }
+
+    /// Returns an iterator over the finalized frames, splitting the samples so that no frame exceeds `max_frame_len` bytes.
+    ///
+    /// The frames are also kept within the [`MAX_LEN`] protocol limit.
+    /// Fails with [`Error::RequestBadParam`] on the first item if a single sample per channel does not fit into a frame.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "heapless")] {
+    /// use dguscard::{curve::CurveWrite, request::HVec};
+    ///
+    /// let samples = [0i16; 100];
+    /// let curve = CurveWrite::new().channel(0, &samples).unwrap();
+    /// for frame in curve.frames::<HVec<64>>(64, true) {
+    ///     let frame = frame.unwrap();
+    ///     assert!(frame.len() <= 64);
+    ///     // Transmit the frame.
+    /// }
+    /// # }
+    /// ```
+    pub fn frames<S: Storage + Default>(
+        &self,
+        max_frame_len: usize,
+        crc: bool,
+    ) -> CurveFrames<'_, 'a, T, S> {
+        let crc_len = if crc { 2 } else { 0 };
+        // Header, length, command code, channel mask and CRC
+        let payload = max_frame_len.saturating_sub(5 + crc_len);
+        // Command code, channel mask and CRC after the length field
+        let payload = payload.min(MAX_LEN as usize - 2 - crc_len);
+        let row_len = self.mask.count_ones() as usize * 2;
+        let rows = payload.checked_div(row_len).unwrap_or(0);
+        let split = CurveSplit {
+            curve: self,
+            rows,
+            idx: 0,
+        };
+        Frames::from_source(split, crc)
+    }
+
+    /// Returns a new request containing the samples in `start..end`.
+    fn request_range<S: Storage>(
+        &self,
+        output: S,
+        start: usize,
+        end: usize,
+    ) -> Result<Request<Write, S>> {
+        let mut request = Request::new(output, Curve { ch: self.mask })?;
+        for i in start..end {
+            for samples in self.selected() {
+                request.push(&samples[i])?;
+            }
+        }
+        Ok(request)
+    }
+
+    /// Returns an iterator over the samples of the selected channels, in ascending channel order.
+    fn selected(&self) -> impl Iterator<Item = &'a [T]> + '_ {
+        self.channels
+            .iter()
+            .enumerate()
+            .filter(|(ch, _)| self.mask & (1 << ch) != 0)
+            .map(|(_, samples)| *samples)
+    }
+}
+
+/// Iterator over the finalized frames of a [`CurveWrite`]
+pub type CurveFrames<'b, 'a, T, S> = Frames<CurveSplit<'b, 'a, T>, S>;
+
+/// Requests of a [`CurveWrite`] split by rows of samples
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CurveSplit<'b, 'a, T> {
+    curve: &'b CurveWrite<'a, T>,
+    rows: usize,
+    idx: usize,
+}
+
+impl<T: Sample> FrameSource for CurveSplit<'_, '_, T> {
+    fn next_request<S: Storage>(&mut self, output: S) -> Option<Result<Request<Write, S>>> {
+        let len = self.curve.len();
+        if self.idx >= len {
+            return None;
+        }
+        if self.rows == 0 {
+            return Some(Err(Error::RequestBadParam));
+        }
+        let start = self.idx;
+        self.idx = len.min(start + self.rows);
+        Some(self.curve.request_range(output, start, self.idx))
+    }
+}
+
+/// Curve buffer state of a channel
+///
+/// Located at the system variable `0x0300 + 2 * ch`, 2 words per channel.
+/// The device advances the pointer and the length as the samples are pushed with a [`Curve`] request.
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::{curve::CurveBuffer, request::Slice, response::Response};
+///
+/// // Read the state of the channel 1
+/// let buf = &mut [0u8; 16];
+/// let frame = CurveBuffer::request(1, Slice::new(buf)).unwrap().finalize(false).unwrap();
+/// assert_eq!(frame, &[0x5A, 0xA5, 4, 0x83, 0x03, 0x02, 2]);
+///
+/// # let rx = &[0x5A, 0xA5, 8, 0x83, 0x03, 0x02, 2, 0x00, 0x20, 0x00, 0x20];
+/// let response = Response::from_bytes(rx, false).unwrap();
+/// let (ch, state) = CurveBuffer::from_response(response).unwrap();
+/// assert_eq!(ch, 1);
+/// assert_eq!(state, CurveBuffer { pointer: 0x20, len: 0x20 });
+///
+/// // Reset the channel 1
+/// let buf = &mut [0u8; 16];
+/// let frame = CurveBuffer::clear_request(1, Slice::new(buf)).unwrap().finalize(false).unwrap();
+/// assert_eq!(frame, &[0x5A, 0xA5, 7, 0x82, 0x03, 0x02, 0, 0, 0, 0]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CurveBuffer {
+    /// Write pointer, i.e. the position of the next sample
+    pub pointer: u16,
+    /// Number of samples in the buffer
+    pub len: u16,
+}
+
+impl CurveBuffer {
+    /// Start address of the channel 0
+    pub const ADDR: u16 = 0x0300;
+    /// Word length per channel
+    pub const WLEN: u8 = 2;
+
+    /// Returns the address of the channel `ch`.
+    ///
+    /// Fails with [`Error::RequestBadParam`] if the channel is out of range.
+    pub const fn addr(ch: u8) -> Result<u16> {
+        if ch as usize >= CHANNELS {
+            return Err(Error::RequestBadParam);
+        }
+        Ok(Self::ADDR + ch as u16 * Self::WLEN as u16)
+    }
+
+    /// Returns a new read request for the state of the channel `ch`.
+    pub fn request<S: Storage>(ch: u8, output: S) -> Result<Request<Read, S>> {
+        Request::new(
+            output,
+            Word {
+                addr: Self::addr(ch)?,
+                cmd: Read { wlen: Self::WLEN },
+            },
+        )
+    }
+
+    /// Returns a new read request for the states of all channels.
+    ///
+    /// The content of the response can be taken as `[CurveBuffer; CHANNELS]`.
+    pub fn request_all<S: Storage>(output: S) -> Result<Request<Read, S>> {
+        Request::new(
+            output,
+            Word {
+                addr: Self::ADDR,
+                cmd: Read {
+                    wlen: CHANNELS as u8 * Self::WLEN,
+                },
+            },
+        )
+    }
+
+    /// Returns a new write request that configures the state of the channel `ch`.
+    pub fn write_request<S: Storage>(&self, ch: u8, output: S) -> Result<Request<Write, S>> {
+        Request::word(output, Self::addr(ch)?, self)
+    }
+
+    /// Returns a new write request that resets the channel `ch`, discarding its samples.
+    pub fn clear_request<S: Storage>(ch: u8, output: S) -> Result<Request<Write, S>> {
+        Self::default().write_request(ch, output)
+    }
+
+    /// Decodes a single channel state from a [`Response::WordData`].
+    ///
+    /// Returns the channel along with its state.
+    pub fn from_response(response: Response) -> Result<(u8, Self)> {
+        let Response::WordData { cmd, mut content } = response else {
+            return Err(Error::ResponseUnexpected);
+        };
+        let offset = cmd.addr.wrapping_sub(Self::ADDR);
+        let ch = offset / Self::WLEN as u16;
+        if offset % Self::WLEN as u16 != 0 || ch as usize >= CHANNELS || cmd.cmd.wlen < Self::WLEN {
+            return Err(Error::ResponseUnexpected);
+        }
+        Ok((ch as u8, content.take()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{request::Slice, response::word_data};
+
+    #[test]
+    fn unequal_len() {
+        let a = [1u16, 2, 3];
+        let b = [1u16, 2];
+        assert_eq!(
+            Err(Error::RequestBadParam),
+            CurveWrite::new()
+                .channel(0, &a)
+                .unwrap()
+                .channel(1, &b)
+                .map(|_| ())
+        );
+        // Replacing the only selected channel is fine
+        let curve = CurveWrite::new()
+            .channel(0, &a)
+            .unwrap()
+            .channel(0, &b)
+            .unwrap();
+        assert_eq!(curve.len(), 2);
+        assert_eq!(curve.mask(), 0b1);
+    }
+
+    #[test]
+    fn bad_channel() {
+        let a = [1i16];
+        assert_eq!(
+            Err(Error::RequestBadParam),
+            CurveWrite::new().channel(8, &a).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn signed() {
+        let a = [-1i16, 2];
+        let curve = CurveWrite::new().channel(7, &a).unwrap();
+        let buf = &mut [0u8; 16];
+        let frame = curve
+            .request(Slice::new(buf))
+            .unwrap()
+            .finalize(false)
+            .unwrap();
+        assert_eq!(frame, &[0x5A, 0xA5, 6, 0x84, 0x80, 0xFF, 0xFF, 0x00, 0x02]);
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn frames() {
+        use crate::request::HVec;
+        let a = [1u16, 2, 3, 4, 5];
+        let b = [6u16, 7, 8, 9, 10];
+        let curve = CurveWrite::new()
+            .channel(0, &a)
+            .unwrap()
+            .channel(1, &b)
+            .unwrap();
+        // 2 rows per frame
+        let mut frames = curve.frames::<HVec<16>>(13, false);
+        assert_eq!(
+            &frames.next().unwrap().unwrap(),
+            &[0x5A, 0xA5, 10, 0x84, 0b11, 0, 1, 0, 6, 0, 2, 0, 7]
+        );
+        assert_eq!(
+            &frames.next().unwrap().unwrap(),
+            &[0x5A, 0xA5, 10, 0x84, 0b11, 0, 3, 0, 8, 0, 4, 0, 9]
+        );
+        assert_eq!(
+            &frames.next().unwrap().unwrap(),
+            &[0x5A, 0xA5, 6, 0x84, 0b11, 0, 5, 0, 10]
+        );
+        assert!(frames.next().is_none());
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn frames_max_len() {
+        use crate::request::HVec;
+        let a = [0x1234u16; 200];
+        let curve = CurveWrite::new().channel(0, &a).unwrap();
+        // 125 rows fit into the length field with CRC
+        let mut frames = curve.frames::<HVec<300>>(300, true);
+        let frame = frames.next().unwrap().unwrap();
+        assert_eq!(frame.len(), 257);
+        assert_eq!(&frame[..5], &[0x5A, 0xA5, 254, 0x84, 0b1]);
+        let frame = frames.next().unwrap().unwrap();
+        assert_eq!(&frame[..5], &[0x5A, 0xA5, 154, 0x84, 0b1]);
+        assert!(frames.next().is_none());
+
+        // The frame error stops the iteration
+        let mut frames = curve.frames::<HVec<64>>(300, true);
+        assert_eq!(Some(Err(Error::RequestBufferFull)), frames.next());
+        assert!(frames.next().is_none());
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn frames_too_small() {
+        use crate::request::HVec;
+        let a = [1u16, 2];
+        let b = [6u16, 7];
+        let curve = CurveWrite::new()
+            .channel(0, &a)
+            .unwrap()
+            .channel(1, &b)
+            .unwrap();
+        let mut frames = curve.frames::<HVec<16>>(8, false);
+        assert_eq!(Some(Err(Error::RequestBadParam)), frames.next());
+        assert!(frames.next().is_none());
+    }
+
+    #[test]
+    fn buffer_addr() {
+        assert_eq!(Ok(0x0300), CurveBuffer::addr(0));
+        assert_eq!(Ok(0x030E), CurveBuffer::addr(7));
+        assert_eq!(Err(Error::RequestBadParam), CurveBuffer::addr(8));
+    }
+
+    #[test]
+    fn buffer_write() {
+        let buf = &mut [0u8; 16];
+        let frame = CurveBuffer {
+            pointer: 0x0123,
+            len: 0x0100,
+        }
+        .write_request(7, Slice::new(buf))
+        .unwrap()
+        .finalize(false)
+        .unwrap();
+        assert_eq!(
+            frame,
+            &[0x5A, 0xA5, 7, 0x82, 0x03, 0x0E, 0x01, 0x23, 0x01, 0x00]
+        );
+    }
+
+    #[test]
+    fn buffer_all() {
+        let mut input = [0u8; 39];
+        input[..7].copy_from_slice(&[0x5A, 0xA5, 36, 0x83, 0x03, 0x00, 16]);
+        input[11..15].copy_from_slice(&[0x00, 0x05, 0x00, 0x04]);
+        let (_, mut content) = word_data(&input);
+        let states: [CurveBuffer; CHANNELS] = content.take().unwrap();
+        assert_eq!(states[0], CurveBuffer::default());
+        assert_eq!(states[1], CurveBuffer { pointer: 5, len: 4 });
+        assert!(content.is_empty());
+    }
+
+    #[test]
+    fn buffer_bad_response() {
+        let input = [0x5A, 0xA5, 8, 0x83, 0x03, 0x01, 2, 0x00, 0x20, 0x00, 0x20];
+        let response = Response::from_bytes(&input, false).unwrap();
+        assert_eq!(
+            Err(Error::ResponseUnexpected),
+            CurveBuffer::from_response(response)
+        );
+    }
+}
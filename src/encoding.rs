@@ -0,0 +1,347 @@
+//! Encoding helper types
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Magic name of [`Pad`], recognized by the dguscard serializer and deserializer
+pub(crate) const PAD: &str = "dguscard::Pad";
+/// Magic name of [`Be`], recognized by the dguscard serializer and deserializer
+pub(crate) const BE: &str = "dguscard::Be";
+/// Magic name of [`Le`], recognized by the dguscard serializer and deserializer
+pub(crate) const LE: &str = "dguscard::Le";
+/// Magic name of [`WordSwapped`], recognized by the dguscard serializer and deserializer
+pub(crate) const WORD_SWAPPED: &str = "dguscard::WordSwapped";
+
+/// Word alignment padding
+///
+/// Serializes into a zero byte if the preceding data ends on a half word, otherwise into nothing.
+/// Deserializes by skipping a byte if the preceding data ends on a half word.
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::{encoding::Pad, request::Request, command::{Word, Write}};
+///
+/// #[derive(serde::Serialize)]
+/// struct MyData {
+///     flag: u8,
+///     _pad: Pad,
+///     word: u16,
+/// }
+///
+/// let buf = &mut [0u8; 16];
+/// let mut frame = Request::with_slice(buf, Word { addr: 0x1000, cmd: Write }).unwrap();
+/// frame.push(&MyData { flag: 1, _pad: Pad, word: 2 }).unwrap();
+/// let tx_bytes = frame.finalize(false).unwrap();
+/// assert_eq!(tx_bytes, &[0x5A, 0xA5, 7, 0x82, 0x10, 0x00, 0x01, 0x00, 0x00, 0x02]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Pad;
+
+impl Serialize for Pad {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_unit_struct(PAD)
+    }
+}
+
+impl<'de> Deserialize<'de> for Pad {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct PadVisitor;
+
+        impl serde::de::Visitor<'_> for PadVisitor {
+            type Value = Pad;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                formatter.write_str("word alignment padding")
+            }
+
+            fn visit_unit<E>(self) -> core::result::Result<Self::Value, E> {
+                Ok(Pad)
+            }
+        }
+
+        deserializer.deserialize_unit_struct(PAD, PadVisitor)
+    }
+}
+
+/// Byte order of the numbers
+///
+/// Can be set for a whole [`Request`][crate::request::Request] or [`Content`][crate::response::Content],
+/// or for a single value with the [`Be`], [`Le`] and [`WordSwapped`] wrappers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Endian {
+    /// Big-endian, the native T5L byte order, e.g. `0x11223344` as `11 22 33 44`
+    #[default]
+    Big,
+    /// Little-endian, e.g. `0x11223344` as `44 33 22 11`
+    Little,
+    /// Big-endian words in reversed order, i.e. low word first, e.g. `0x11223344` as `33 44 11 22`
+    WordSwapped,
+}
+
+impl Endian {
+    /// Reorders big-endian bytes into this byte order, or this byte order into big-endian bytes.
+    #[inline]
+    pub(crate) fn reorder(self, bytes: &mut [u8]) {
+        match self {
+            Endian::Big => (),
+            Endian::Little => bytes.reverse(),
+            Endian::WordSwapped => {
+                bytes.reverse();
+                bytes.chunks_exact_mut(2).for_each(|word| word.swap(0, 1));
+            }
+        }
+    }
+}
+
+macro_rules! endian_wrapper {
+    ($(#[$attr:meta])* $ty:ident, $name:ident) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+        pub struct $ty<T>(pub T);
+
+        impl<T: Serialize> Serialize for $ty<T> {
+            fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_newtype_struct($name, &self.0)
+            }
+        }
+
+        impl<'de, T: Deserialize<'de>> Deserialize<'de> for $ty<T> {
+            fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                struct NewtypeVisitor<T>(core::marker::PhantomData<T>);
+
+                impl<'de, T: Deserialize<'de>> serde::de::Visitor<'de> for NewtypeVisitor<T> {
+                    type Value = $ty<T>;
+
+                    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                        formatter.write_str($name)
+                    }
+
+                    fn visit_newtype_struct<D>(
+                        self,
+                        deserializer: D,
+                    ) -> core::result::Result<Self::Value, D::Error>
+                    where
+                        D: Deserializer<'de>,
+                    {
+                        T::deserialize(deserializer).map($ty)
+                    }
+                }
+
+                deserializer.deserialize_newtype_struct($name, NewtypeVisitor(core::marker::PhantomData))
+            }
+        }
+    };
+}
+
+endian_wrapper! {
+    /// Big-endian value wrapper
+    ///
+    /// Encodes the numbers of `T` as big-endian regardless of the current byte order.
+    Be, BE
+}
+
+endian_wrapper! {
+    /// Little-endian value wrapper
+    ///
+    /// Encodes the numbers of `T` as little-endian regardless of the current byte order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use dguscard::{encoding::Le, request::Request, command::{Word, Write}};
+    ///
+    /// let buf = &mut [0u8; 16];
+    /// let mut frame = Request::with_slice(buf, Word { addr: 0x1000, cmd: Write }).unwrap();
+    /// frame.push(&Le(0x1122_3344u32)).unwrap();
+    /// let tx_bytes = frame.finalize(false).unwrap();
+    /// assert_eq!(tx_bytes, &[0x5A, 0xA5, 7, 0x82, 0x10, 0x00, 0x44, 0x33, 0x22, 0x11]);
+    /// ```
+    Le, LE
+}
+
+endian_wrapper! {
+    /// Word-swapped value wrapper
+    ///
+    /// Encodes the numbers of `T` as big-endian words in reversed order, i.e. low word first,
+    /// regardless of the current byte order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use dguscard::{encoding::WordSwapped, request::Request, command::{Word, Write}};
+    ///
+    /// let buf = &mut [0u8; 16];
+    /// let mut frame = Request::with_slice(buf, Word { addr: 0x1000, cmd: Write }).unwrap();
+    /// frame.push(&WordSwapped(0x1122_3344u32)).unwrap();
+    /// let tx_bytes = frame.finalize(false).unwrap();
+    /// assert_eq!(tx_bytes, &[0x5A, 0xA5, 7, 0x82, 0x10, 0x00, 0x33, 0x44, 0x11, 0x22]);
+    /// ```
+    WordSwapped, WORD_SWAPPED
+}
+
+/// Encoding of the `bool` values
+///
+/// Can be set for a whole [`Request`][crate::request::Request] or [`Content`][crate::response::Content].
+/// Single fields can use the [`ByteBool`], [`LenientBool`] and [`OnOff`] wrappers instead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BoolEncoding {
+    /// A word of `0x0000` or `0x0001`, anything else fails to deserialize
+    #[default]
+    Word,
+    /// A byte of `0x00` or `0x01`, anything else fails to deserialize
+    Byte,
+    /// A word of `0x0000` or `0x0001`, any non-zero value deserializes as `true`
+    LenientWord,
+    /// A byte of `0x00` or `0x01`, any non-zero value deserializes as `true`
+    LenientByte,
+    /// A word of the given values, anything else fails to deserialize
+    Custom {
+        /// Value of `true`
+        on: u16,
+        /// Value of `false`
+        off: u16,
+    },
+}
+
+/// Byte sized `bool` wrapper
+///
+/// Encodes as a byte of `0x00` or `0x01` regardless of the current [`BoolEncoding`].
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::{encoding::ByteBool, request::Request, command::{Word, Write}};
+///
+/// let buf = &mut [0u8; 16];
+/// let mut frame = Request::with_slice(buf, Word { addr: 0x1000, cmd: Write }).unwrap();
+/// frame.push(&(ByteBool(true), ByteBool(false))).unwrap();
+/// let tx_bytes = frame.finalize(false).unwrap();
+/// assert_eq!(tx_bytes, &[0x5A, 0xA5, 5, 0x82, 0x10, 0x00, 0x01, 0x00]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ByteBool(pub bool);
+
+impl Serialize for ByteBool {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u8(self.0.into())
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteBool {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match u8::deserialize(deserializer)? {
+            0 => Ok(ByteBool(false)),
+            1 => Ok(ByteBool(true)),
+            v => Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(v.into()),
+                &"0 or 1",
+            )),
+        }
+    }
+}
+
+/// Lenient `bool` wrapper
+///
+/// Encodes as a word of `0x0000` or `0x0001`, but any non-zero word deserializes as `true`.
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::{encoding::LenientBool, response::Response};
+///
+/// let rx = &[0x5A, 0xA5, 6, 0x83, 0x10, 0x00, 1, 0x00, 0x0D];
+/// let Response::WordData { mut content, .. } = Response::from_bytes(rx, false).unwrap() else {
+///     panic!("Unexpected response");
+/// };
+/// let key: LenientBool = content.take().unwrap();
+/// assert!(key.0);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LenientBool(pub bool);
+
+impl Serialize for LenientBool {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u16(self.0.into())
+    }
+}
+
+impl<'de> Deserialize<'de> for LenientBool {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        u16::deserialize(deserializer).map(|v| LenientBool(v != 0))
+    }
+}
+
+/// `bool` wrapper with custom on/off values
+///
+/// Encodes as a word of `ON` or `OFF`, anything else fails to deserialize.
+/// Useful for the controls that return key codes instead of `0x0000`/`0x0001`.
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::{encoding::OnOff, request::Request, command::{Word, Write}};
+///
+/// let buf = &mut [0u8; 16];
+/// let mut frame = Request::with_slice(buf, Word { addr: 0x1000, cmd: Write }).unwrap();
+/// frame.push(&OnOff::<0x5A5A, 0xA5A5>(true)).unwrap();
+/// let tx_bytes = frame.finalize(false).unwrap();
+/// assert_eq!(tx_bytes, &[0x5A, 0xA5, 5, 0x82, 0x10, 0x00, 0x5A, 0x5A]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OnOff<const ON: u16, const OFF: u16>(pub bool);
+
+impl<const ON: u16, const OFF: u16> Serialize for OnOff<ON, OFF> {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u16(if self.0 { ON } else { OFF })
+    }
+}
+
+impl<'de, const ON: u16, const OFF: u16> Deserialize<'de> for OnOff<ON, OFF> {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match u16::deserialize(deserializer)? {
+            v if v == ON => Ok(OnOff(true)),
+            v if v == OFF => Ok(OnOff(false)),
+            v => Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(v.into()),
+                &"the on or off value",
+            )),
+        }
+    }
+}
@@ -9,6 +9,12 @@ pub type Result<T> = ::core::result::Result<T, Error>;
 pub enum Error {
     /// The request buffer is full
     RequestBufferFull,
+    /// The request is longer than its length limit
+    RequestTooLarge,
+    /// A request parameter is out of range
+    RequestBadParam,
+    /// The request payload ends on a half word
+    RequestMisaligned,
     /// Response header mismatch
     ResponseBadHeader,
     /// Response length is more than the buffer size
@@ -21,6 +27,10 @@ pub enum Error {
     ResponseBadAck,
     /// Response CRC mismatch
     ResponseBadCrc,
+    /// Response doesn't match the expected command
+    ResponseUnexpected,
+    /// The address is outside of the response content
+    ResponseBadAddr,
     /// Found an enum discriminant that was > u16::max_value()
     SerializeBadEnum,
     /// Hit the end of buffer, expected more data
@@ -29,6 +39,8 @@ pub enum Error {
     DeserializeBadBool,
     /// Found an Option discriminant that wasn't 0 or 1
     DeserializeBadOption,
+    /// Found a string that wasn't valid UTF-8
+    DeserializeBadUtf8,
     /// The accumulator buffer is full
     AccumulateBufferFull,
     /// dguscard will never implement this
@@ -49,16 +61,22 @@ impl Display for Error {
             "{}",
             match self {
                 RequestBufferFull => "The request buffer is full",
+                RequestTooLarge => "The request is longer than its length limit",
+                RequestBadParam => "A request parameter is out of range",
+                RequestMisaligned => "The request payload ends on a half word",
                 ResponseBadHeader => "Response header mismatch",
                 ResponseTooLarge => "Response length is more than the buffer size",
                 ResponseBadLen => "Response length is less than the minimum proper response length",
                 ResponseUnknownCmd => "Unknown response command",
                 ResponseBadAck => "Bad Ack response",
                 ResponseBadCrc => "Response CRC mismatch",
+                ResponseUnexpected => "Response doesn't match the expected command",
+                ResponseBadAddr => "The address is outside of the response content",
                 SerializeBadEnum => "Found an enum discriminant that was > u16::max_value()",
                 DeserializeUnexpectedEnd => "Hit the end of buffer, expected more data",
                 DeserializeBadBool => "Found a bool that wasn't 0 or 1",
                 DeserializeBadOption => "Found an Option discriminant that wasn't 0 or 1",
+                DeserializeBadUtf8 => "Found a string that wasn't valid UTF-8",
                 AccumulateBufferFull => "The accumulator buffer is full",
                 WontImplement => "dguscard will never implement this",
                 NotYetImplemented => "dguscard may support this",
@@ -0,0 +1,146 @@
+//! Fixed-point numeric types
+
+use serde::{Deserialize, Serialize};
+
+trait Sealed {}
+
+/// Fixed-point raw integer trait (sealed)
+///
+/// Implemented for [`u16`], [`i16`], [`i32`] and [`i64`].
+#[allow(private_bounds)]
+pub trait Raw: Copy + Sealed {
+    /// Converts from a scaled value, rounding to the nearest and saturating at the bounds.
+    fn from_scaled(v: f32) -> Self;
+    /// Converts into a scaled value.
+    fn into_scaled(self) -> f32;
+}
+
+macro_rules! impl_raw {
+    ($($ty:ident)+) => ($(
+        impl Sealed for $ty {}
+        impl Raw for $ty {
+            #[inline]
+            fn from_scaled(v: f32) -> Self {
+                // `as` saturates at the bounds and maps NaN to zero
+                (if v < 0.0 { v - 0.5 } else { v + 0.5 }) as $ty
+            }
+
+            #[inline]
+            fn into_scaled(self) -> f32 {
+                self as f32
+            }
+        }
+    )+)
+}
+
+impl_raw! { u16 i16 i32 i64 }
+
+/// Fixed-point number with `DECIMALS` decimal places
+///
+/// Encoded as the raw integer `T`, i.e. the value multiplied by `10^DECIMALS`,
+/// as displayed by a data variable control configured with the same number of decimal places.
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::fixed::Fixed;
+///
+/// let temperature = Fixed::<1, i16>::from_f32(23.54);
+/// assert_eq!(temperature.raw(), 235);
+/// assert_eq!(temperature.to_f32(), 23.5);
+///
+/// // Saturates at the bounds of the raw integer
+/// assert_eq!(Fixed::<2, u16>::from_f32(-1.0).raw(), 0);
+/// assert_eq!(Fixed::<2, u16>::from_f32(1000.0).raw(), u16::MAX);
+/// ```
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Fixed<const DECIMALS: u8, T>(T);
+
+impl<const DECIMALS: u8, T: Raw> Fixed<DECIMALS, T> {
+    /// Scale factor, i.e. `10^DECIMALS`
+    pub const SCALE: f32 = 10u64.pow(DECIMALS as u32) as f32;
+
+    /// Returns a new fixed-point number from the raw integer.
+    pub const fn from_raw(raw: T) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the raw integer.
+    pub const fn raw(self) -> T {
+        self.0
+    }
+
+    /// Returns a new fixed-point number from a real value, rounding to the nearest and saturating at the bounds.
+    pub fn from_f32(v: f32) -> Self {
+        Self(T::from_scaled(v * Self::SCALE))
+    }
+
+    /// Returns the real value.
+    pub fn to_f32(self) -> f32 {
+        self.0.into_scaled() / Self::SCALE
+    }
+}
+
+impl<const DECIMALS: u8, T: Raw> From<f32> for Fixed<DECIMALS, T> {
+    fn from(v: f32) -> Self {
+        Self::from_f32(v)
+    }
+}
+
+impl<const DECIMALS: u8, T: Raw> From<Fixed<DECIMALS, T>> for f32 {
+    fn from(v: Fixed<DECIMALS, T>) -> Self {
+        v.to_f32()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        command::{Word, Write},
+        request::Request,
+        response::word_data,
+    };
+
+    #[test]
+    fn rounding() {
+        assert_eq!(Fixed::<1, i16>::from_f32(-23.55).raw(), -236);
+        assert_eq!(Fixed::<1, i16>::from_f32(-23.54).raw(), -235);
+        assert_eq!(Fixed::<0, i32>::from_f32(0.5).raw(), 1);
+        assert_eq!(Fixed::<3, i64>::from_f32(1.2345).raw(), 1235);
+        assert_eq!(Fixed::<2, i16>::from_f32(f32::NAN).raw(), 0);
+        assert_eq!(Fixed::<2, i16>::from_f32(-1e9).raw(), i16::MIN);
+    }
+
+    #[test]
+    fn serialize() {
+        let buf = &mut [0u8; 16];
+        let mut request = Request::with_slice(
+            buf,
+            Word {
+                addr: 0x1000,
+                cmd: Write,
+            },
+        )
+        .unwrap();
+        request.push(&Fixed::<1, i16>::from_f32(-0.1)).unwrap();
+        request.push(&Fixed::<2, i32>::from_f32(1.0)).unwrap();
+        let frame = request.finalize(false).unwrap();
+        assert_eq!(
+            frame,
+            &[0x5A, 0xA5, 9, 0x82, 0x10, 0x00, 0xFF, 0xFF, 0x00, 0x00, 0x00, 100]
+        );
+    }
+
+    #[test]
+    fn deserialize() {
+        let input = [0x5A, 0xA5, 8, 0x83, 0x10, 0x00, 2, 0x00, 235, 0xFF, 0xFE];
+        let (_, mut content) = word_data(&input);
+        let (a, b): (Fixed<1, u16>, Fixed<2, i16>) = content.take().unwrap();
+        assert_eq!(a.to_f32(), 23.5);
+        assert_eq!(b.to_f32(), -0.02);
+    }
+}
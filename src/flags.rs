@@ -0,0 +1,216 @@
+//! Bit flag types
+
+use crate::{
+    command::Write,
+    request::{Request, Storage},
+    Error, Result,
+};
+use serde::{Deserialize, Serialize};
+
+/// 16 bit flags of a single VP
+///
+/// Encoded as a single big-endian word, e.g. for bit indicator icons.
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::{flags::WordFlags, request::Slice};
+///
+/// let old = [WordFlags(0x0000), WordFlags(0x0000), WordFlags(0x0000)];
+/// let mut new = old;
+/// new[1].set(0, true).unwrap();
+/// new[1].set(15, true).unwrap();
+/// assert!(new[1].get(15).unwrap());
+///
+/// // Only the changed word is written
+/// let buf = &mut [0u8; 16];
+/// let frame = WordFlags::diff_request(0x5000, &old, &new, Slice::new(buf))
+///     .unwrap()
+///     .unwrap()
+///     .finalize(false)
+///     .unwrap();
+/// assert_eq!(frame, &[0x5A, 0xA5, 5, 0x82, 0x50, 0x01, 0x80, 0x01]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct WordFlags(pub u16);
+
+impl WordFlags {
+    /// Returns the bits.
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Returns the state of the bit.
+    ///
+    /// Fails with [`Error::RequestBadParam`] if `bit` is not less than 16.
+    pub const fn get(self, bit: u8) -> Result<bool> {
+        match Self::mask(bit) {
+            Ok(mask) => Ok(self.0 & mask != 0),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Sets the state of the bit.
+    ///
+    /// Fails with [`Error::RequestBadParam`] if `bit` is not less than 16.
+    pub fn set(&mut self, bit: u8, value: bool) -> Result<()> {
+        let mask = Self::mask(bit)?;
+        if value {
+            self.0 |= mask;
+        } else {
+            self.0 &= !mask;
+        }
+        Ok(())
+    }
+
+    /// Toggles the state of the bit.
+    ///
+    /// Fails with [`Error::RequestBadParam`] if `bit` is not less than 16.
+    pub fn toggle(&mut self, bit: u8) -> Result<()> {
+        self.0 ^= Self::mask(bit)?;
+        Ok(())
+    }
+
+    /// Returns the bits that differ from `other`.
+    pub const fn changed(self, other: Self) -> Self {
+        Self(self.0 ^ other.0)
+    }
+
+    /// Returns a new write request containing the smallest span of words that differ between `old` and `new`,
+    /// with the first word at `vp`, or [`None`] if nothing has changed.
+    ///
+    /// Fails with [`Error::RequestBadParam`] if `old` and `new` have different lengths.
+    pub fn diff_request<S: Storage>(
+        vp: u16,
+        old: &[Self],
+        new: &[Self],
+        output: S,
+    ) -> Result<Option<Request<Write, S>>> {
+        if old.len() != new.len() {
+            return Err(Error::RequestBadParam);
+        }
+        let changed = |(old, new): (&Self, &Self)| old != new;
+        let Some(first) = old.iter().zip(new).position(changed) else {
+            return Ok(None);
+        };
+        let last = old.iter().zip(new).rposition(changed).unwrap_or(first);
+        let addr = u16::try_from(first)
+            .ok()
+            .and_then(|first| vp.checked_add(first))
+            .ok_or(Error::RequestBadParam)?;
+        Request::word(output, addr, &new[first..=last]).map(Some)
+    }
+
+    const fn mask(bit: u8) -> Result<u16> {
+        if bit >= 16 {
+            return Err(Error::RequestBadParam);
+        }
+        Ok(1 << bit)
+    }
+}
+
+impl From<u16> for WordFlags {
+    fn from(bits: u16) -> Self {
+        Self(bits)
+    }
+}
+
+impl From<WordFlags> for u16 {
+    fn from(flags: WordFlags) -> Self {
+        flags.0
+    }
+}
+
+#[cfg(feature = "bitflags")]
+impl WordFlags {
+    /// Returns new word flags from a [`bitflags`] type, retaining all the bits.
+    pub fn from_flags<B: bitflags::Flags<Bits = u16>>(flags: B) -> Self {
+        Self(flags.bits())
+    }
+
+    /// Returns a [`bitflags`] type, retaining all the bits.
+    pub fn to_flags<B: bitflags::Flags<Bits = u16>>(self) -> B {
+        B::from_bits_retain(self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{request::Slice, response::word_data};
+
+    #[test]
+    fn bits() {
+        let mut flags = WordFlags::default();
+        flags.set(3, true).unwrap();
+        flags.toggle(4).unwrap();
+        assert_eq!(flags.bits(), 0b11000);
+        flags.set(3, false).unwrap();
+        assert_eq!(Ok(false), flags.get(3));
+        assert_eq!(Ok(true), flags.get(4));
+        assert_eq!(flags.changed(WordFlags(0b10001)), WordFlags(0b00001));
+    }
+
+    #[test]
+    fn bit_out_of_range() {
+        let mut flags = WordFlags(0xFFFF);
+        assert_eq!(Err(Error::RequestBadParam), flags.get(16));
+        assert_eq!(Err(Error::RequestBadParam), flags.set(16, false));
+        assert_eq!(Err(Error::RequestBadParam), flags.toggle(16));
+        assert_eq!(flags, WordFlags(0xFFFF));
+    }
+
+    #[test]
+    fn diff_span() {
+        let old = [WordFlags(1), WordFlags(2), WordFlags(3), WordFlags(4)];
+        let new = [WordFlags(1), WordFlags(0), WordFlags(3), WordFlags(0)];
+        let buf = &mut [0u8; 16];
+        let frame = WordFlags::diff_request(0x5000, &old, &new, Slice::new(buf))
+            .unwrap()
+            .unwrap()
+            .finalize(false)
+            .unwrap();
+        assert_eq!(
+            frame,
+            &[0x5A, 0xA5, 9, 0x82, 0x50, 0x01, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn diff_none() {
+        let old = [WordFlags(1), WordFlags(2)];
+        let buf = &mut [0u8; 16];
+        assert!(WordFlags::diff_request(0x5000, &old, &old, Slice::new(buf))
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            Err(Error::RequestBadParam),
+            WordFlags::diff_request(0x5000, &old, &old[1..], Slice::new(buf)).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn deserialize() {
+        let input = [0x5A, 0xA5, 6, 0x83, 0x50, 0x00, 1, 0x80, 0x01];
+        let (_, mut content) = word_data(&input);
+        let flags: WordFlags = content.take().unwrap();
+        assert_eq!(Ok(true), flags.get(0));
+        assert_eq!(Ok(true), flags.get(15));
+    }
+
+    #[cfg(feature = "bitflags")]
+    #[test]
+    fn bitflags() {
+        bitflags::bitflags! {
+            #[derive(Debug, PartialEq, Eq)]
+            struct Alarms: u16 {
+                const OVERHEAT = 1 << 0;
+                const DOOR = 1 << 3;
+            }
+        }
+        let flags = WordFlags::from_flags(Alarms::DOOR | Alarms::OVERHEAT);
+        assert_eq!(flags, WordFlags(0b1001));
+        assert_eq!(flags.to_flags::<Alarms>(), Alarms::DOOR | Alarms::OVERHEAT);
+    }
+}
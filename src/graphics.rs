@@ -0,0 +1,298 @@
+//! Basic graphics drawing helpers
+//!
+//! A basic graphics control draws the instructions stored in its VP block:
+//! a command word, the number of items, the items and a terminator word.
+
+use crate::{
+    color::Rgb565,
+    command::Write,
+    request::{Request, Storage, WordFrames},
+    Error, Result,
+};
+use serde::{ser::SerializeTuple, Deserialize, Serialize, Serializer};
+
+trait Sealed {}
+
+/// Drawing primitive trait (sealed)
+///
+/// Implemented by all drawing primitives.
+#[allow(private_bounds)]
+pub trait Primitive: Serialize + Sealed {
+    /// Drawing command code
+    const CMD: u16;
+}
+
+/// Point
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Point {
+    /// X coordinate
+    pub x: u16,
+    /// Y coordinate
+    pub y: u16,
+}
+
+impl Point {
+    /// Returns a new point.
+    pub const fn new(x: u16, y: u16) -> Self {
+        Self { x, y }
+    }
+}
+
+/// Dot
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Dot {
+    /// Position
+    pub at: Point,
+    /// Color
+    pub color: Rgb565,
+}
+impl Sealed for Dot {}
+impl Primitive for Dot {
+    const CMD: u16 = 0x0001;
+}
+
+/// Line segment
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Line {
+    /// Start point
+    pub start: Point,
+    /// End point
+    pub end: Point,
+    /// Color
+    pub color: Rgb565,
+}
+impl Sealed for Line {}
+impl Primitive for Line {
+    const CMD: u16 = 0x0002;
+}
+
+/// Rectangle outline
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Rect {
+    /// Top left corner
+    pub top_left: Point,
+    /// Bottom right corner
+    pub bottom_right: Point,
+    /// Color
+    pub color: Rgb565,
+}
+impl Sealed for Rect {}
+impl Primitive for Rect {
+    const CMD: u16 = 0x0003;
+}
+
+/// Filled rectangle
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FilledRect {
+    /// Top left corner
+    pub top_left: Point,
+    /// Bottom right corner
+    pub bottom_right: Point,
+    /// Color
+    pub color: Rgb565,
+}
+impl Sealed for FilledRect {}
+impl Primitive for FilledRect {
+    const CMD: u16 = 0x0004;
+}
+
+/// Circle outline
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Circle {
+    /// Center
+    pub center: Point,
+    /// Radius
+    pub radius: u16,
+    /// Color
+    pub color: Rgb565,
+}
+impl Sealed for Circle {}
+impl Primitive for Circle {
+    const CMD: u16 = 0x0005;
+}
+
+/// Area copy
+///
+/// Copies an area of a page to the target position of the current page.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AreaCopy {
+    /// Source page ID
+    pub page: u16,
+    /// Top left corner of the source area
+    pub top_left: Point,
+    /// Bottom right corner of the source area
+    pub bottom_right: Point,
+    /// Top left corner of the target area
+    pub target: Point,
+}
+impl Sealed for AreaCopy {}
+impl Primitive for AreaCopy {
+    const CMD: u16 = 0x0006;
+}
+
+/// Drawing list
+///
+/// Serializes into a full drawing command block, i.e. command, count, items and terminator.
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::{color::Rgb565, graphics::{DrawList, Line, Point}, request::Slice};
+///
+/// let lines = [
+///     Line { start: Point::new(0, 0), end: Point::new(10, 10), color: Rgb565::RED },
+///     Line { start: Point::new(10, 10), end: Point::new(20, 0), color: Rgb565::GREEN },
+/// ];
+/// let list = DrawList::new(&lines).unwrap();
+///
+/// let buf = &mut [0u8; 64];
+/// let frame = list.request(0x5000, Slice::new(buf)).unwrap().finalize(false).unwrap();
+/// assert_eq!(
+///     frame,
+///     &[
+///         0x5A, 0xA5, 29, 0x82, 0x50, 0x00,
+///         0x00, 0x02, 0x00, 0x02,                         // command, count
+///         0, 0, 0, 0, 0, 10, 0, 10, 0xF8, 0x00,           // first line
+///         0, 10, 0, 10, 0, 20, 0, 0, 0x07, 0xE0,          // second line
+///         0xFF, 0x00,                                     // terminator
+///     ]
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DrawList<'a, P> {
+    items: &'a [P],
+}
+
+impl<'a, P: Primitive> DrawList<'a, P> {
+    /// Terminator word
+    const END: u16 = 0xFF00;
+
+    /// Returns a new drawing list.
+    ///
+    /// Fails with [`Error::RequestBadParam`] if there are more than [`u16::MAX`] items.
+    pub fn new(items: &'a [P]) -> Result<Self> {
+        if items.len() > u16::MAX as usize {
+            return Err(Error::RequestBadParam);
+        }
+        Ok(Self { items })
+    }
+
+    /// Returns a new write request containing the drawing list written to the VP block at `vp`.
+    pub fn request<S: Storage>(&self, vp: u16, output: S) -> Result<Request<Write, S>> {
+        Request::word(output, vp, self)
+    }
+
+    /// Returns an iterator over the finalized frames, splitting the drawing list written to the VP block at `vp`
+    /// so that no frame exceeds `max_frame_len` bytes.
+    ///
+    /// Each frame continues at the address where the previous one left off.
+    pub fn frames<S: Storage + Default>(
+        &self,
+        vp: u16,
+        max_frame_len: usize,
+        crc: bool,
+    ) -> WordFrames<'_, Self, S> {
+        WordFrames::new(self, vp, max_frame_len, crc)
+    }
+}
+
+impl<P: Primitive> Serialize for DrawList<'_, P> {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut tuple = serializer.serialize_tuple(self.items.len() + 3)?;
+        tuple.serialize_element(&P::CMD)?;
+        tuple.serialize_element(&(self.items.len() as u16))?;
+        for item in self.items {
+            tuple.serialize_element(item)?;
+        }
+        tuple.serialize_element(&Self::END)?;
+        tuple.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::Slice;
+
+    #[test]
+    fn circles() {
+        let circles = [Circle {
+            center: Point::new(0x0100, 0x0080),
+            radius: 0x0020,
+            color: Rgb565::BLUE,
+        }];
+        let buf = &mut [0u8; 32];
+        let frame = DrawList::new(&circles)
+            .unwrap()
+            .request(0x5000, Slice::new(buf))
+            .unwrap()
+            .finalize(false)
+            .unwrap();
+        assert_eq!(
+            frame,
+            &[
+                0x5A, 0xA5, 17, 0x82, 0x50, 0x00, 0x00, 0x05, 0x00, 0x01, 0x01, 0x00, 0x00, 0x80,
+                0x00, 0x20, 0x00, 0x1F, 0xFF, 0x00
+            ]
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let dots: [Dot; 0] = [];
+        let buf = &mut [0u8; 32];
+        let frame = DrawList::new(&dots)
+            .unwrap()
+            .request(0x5000, Slice::new(buf))
+            .unwrap()
+            .finalize(false)
+            .unwrap();
+        assert_eq!(
+            frame,
+            &[0x5A, 0xA5, 9, 0x82, 0x50, 0x00, 0x00, 0x01, 0x00, 0x00, 0xFF, 0x00]
+        );
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn frames() {
+        use crate::request::HVec;
+        let dots = [
+            Dot {
+                at: Point::new(1, 2),
+                color: Rgb565::WHITE,
+            },
+            Dot {
+                at: Point::new(3, 4),
+                color: Rgb565::BLACK,
+            },
+        ];
+        let list = DrawList::new(&dots).unwrap();
+        // 4 words per frame
+        let mut frames = list.frames::<HVec<16>>(0x5000, 14, false);
+        assert_eq!(
+            &frames.next().unwrap().unwrap(),
+            &[0x5A, 0xA5, 11, 0x82, 0x50, 0x00, 0x00, 0x01, 0x00, 0x02, 0x00, 0x01, 0x00, 0x02]
+        );
+        assert_eq!(
+            &frames.next().unwrap().unwrap(),
+            &[0x5A, 0xA5, 11, 0x82, 0x50, 0x04, 0xFF, 0xFF, 0x00, 0x03, 0x00, 0x04, 0x00, 0x00]
+        );
+        assert_eq!(
+            &frames.next().unwrap().unwrap(),
+            &[0x5A, 0xA5, 5, 0x82, 0x50, 0x08, 0xFF, 0x00]
+        );
+        assert!(frames.next().is_none());
+    }
+}
@@ -0,0 +1,205 @@
+//! Icon and animation variable helpers
+
+use crate::{
+    command::Write,
+    request::{Request, Serializer, Slice, Storage},
+    sp, Error, Result,
+};
+use serde::{Deserialize, Serialize};
+
+/// Icon variable value
+///
+/// The value written into the VP of a variable icon control, selecting the displayed icon.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct IconId(pub u16);
+
+impl IconId {
+    /// Returns the icon ID of a value encoded as a single word, e.g. the index of a unit enum variant.
+    ///
+    /// Fails with [`Error::RequestBadParam`] if the value is not encoded as a single word.
+    pub fn of<T: Serialize + ?Sized>(value: &T) -> Result<Self> {
+        let buf = &mut [0u8; 2];
+        let mut serializer = Serializer::new(Slice::new(buf));
+        value
+            .serialize(&mut serializer)
+            .map_err(|_| Error::RequestBadParam)?;
+        let bytes = serializer.output.finalize();
+        let word = <[u8; 2]>::try_from(&*bytes).map_err(|_| Error::RequestBadParam)?;
+        Ok(Self(u16::from_be_bytes(word)))
+    }
+}
+
+/// Icon variable range
+///
+/// The values configured for the minimum and maximum icons of a variable icon control.
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::{icon::{IconId, IconRange}, request::Slice};
+///
+/// #[derive(serde::Serialize)]
+/// enum Battery {
+///     Empty,
+///     Low,
+///     Half,
+///     Full,
+/// }
+///
+/// let range = IconRange::new(0, 3);
+/// assert_eq!(range.map(&Battery::Half), Ok(IconId(2)));
+///
+/// let buf = &mut [0u8; 16];
+/// let frame = range.request(0x5000, &Battery::Full, Slice::new(buf)).unwrap().finalize(false).unwrap();
+/// assert_eq!(frame, &[0x5A, 0xA5, 5, 0x82, 0x50, 0x00, 0x00, 0x03]);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct IconRange {
+    /// Value displayed with the minimum icon
+    pub min: u16,
+    /// Value displayed with the maximum icon
+    pub max: u16,
+}
+
+impl IconRange {
+    /// Returns a new range.
+    pub const fn new(min: u16, max: u16) -> Self {
+        Self { min, max }
+    }
+
+    /// Returns the icon ID if it is within the range.
+    ///
+    /// Fails with [`Error::RequestBadParam`] otherwise.
+    pub const fn check(&self, id: IconId) -> Result<IconId> {
+        if id.0 < self.min || id.0 > self.max {
+            return Err(Error::RequestBadParam);
+        }
+        Ok(id)
+    }
+
+    /// Maps a value to an icon ID within the range, see [`IconId::of`].
+    pub fn map<T: Serialize + ?Sized>(&self, value: &T) -> Result<IconId> {
+        self.check(IconId::of(value)?)
+    }
+
+    /// Returns a new write request that displays the icon mapped from `value`, with the icon variable at `vp`.
+    pub fn request<T: Serialize + ?Sized, S: Storage>(
+        &self,
+        vp: u16,
+        value: &T,
+        output: S,
+    ) -> Result<Request<Write, S>> {
+        let id = self.map(value)?;
+        Request::word(output, vp, &id)
+    }
+}
+
+impl From<&sp::Icon> for IconRange {
+    fn from(icon: &sp::Icon) -> Self {
+        Self::new(icon.value_min, icon.value_max)
+    }
+}
+
+/// Animation icon control
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AnimationControl {
+    /// Play the animation
+    Play,
+    /// Stop the animation, displaying the stop icon
+    Stop,
+    /// Write a raw value, any value other than the start and stop values hides the animation
+    Frame(u16),
+}
+
+/// Animation icon variable
+///
+/// The values configured for starting and stopping an animation icon control.
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::{icon::{Animation, AnimationControl}, request::Slice};
+///
+/// let animation = Animation::new(1, 0);
+///
+/// let buf = &mut [0u8; 16];
+/// let frame = animation
+///     .request(0x5000, AnimationControl::Play, Slice::new(buf))
+///     .unwrap()
+///     .finalize(false)
+///     .unwrap();
+/// assert_eq!(frame, &[0x5A, 0xA5, 5, 0x82, 0x50, 0x00, 0x00, 0x01]);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Animation {
+    /// Value that plays the animation
+    pub start: u16,
+    /// Value that stops the animation
+    pub stop: u16,
+}
+
+impl Animation {
+    /// Returns a new animation variable.
+    pub const fn new(start: u16, stop: u16) -> Self {
+        Self { start, stop }
+    }
+
+    /// Returns the value for the control.
+    pub const fn value(&self, control: AnimationControl) -> u16 {
+        match control {
+            AnimationControl::Play => self.start,
+            AnimationControl::Stop => self.stop,
+            AnimationControl::Frame(value) => value,
+        }
+    }
+
+    /// Returns a new write request that controls the animation, with the animation variable at `vp`.
+    pub fn request<S: Storage>(
+        &self,
+        vp: u16,
+        control: AnimationControl,
+        output: S,
+    ) -> Result<Request<Write, S>> {
+        Request::word(output, vp, &self.value(control))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    enum Mode {
+        _Off,
+        _Auto,
+        Manual,
+    }
+
+    #[test]
+    fn of() {
+        assert_eq!(Ok(IconId(2)), IconId::of(&Mode::Manual));
+        assert_eq!(Ok(IconId(0x1234)), IconId::of(&0x1234u16));
+        assert_eq!(Err(Error::RequestBadParam), IconId::of(&0x1234u32));
+        assert_eq!(Err(Error::RequestBadParam), IconId::of(&0x12u8));
+    }
+
+    #[test]
+    fn range() {
+        let range = IconRange::new(1, 2);
+        assert_eq!(Ok(IconId(2)), range.map(&Mode::Manual));
+        assert_eq!(Err(Error::RequestBadParam), range.map(&Mode::_Off));
+        assert_eq!(Err(Error::RequestBadParam), range.check(IconId(3)));
+    }
+
+    #[test]
+    fn animation() {
+        let animation = Animation::new(0x10, 0x20);
+        assert_eq!(0x10, animation.value(AnimationControl::Play));
+        assert_eq!(0x20, animation.value(AnimationControl::Stop));
+        assert_eq!(0x30, animation.value(AnimationControl::Frame(0x30)));
+    }
+}
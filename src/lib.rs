@@ -8,9 +8,24 @@
 
 mod error;
 
+// Lets the derive macros refer to `::dguscard` within the crate tests
+#[cfg(all(test, feature = "derive"))]
+extern crate self as dguscard;
+
+pub mod color;
 pub mod command;
+pub mod curve;
+pub mod encoding;
+pub mod fixed;
+pub mod flags;
+pub mod graphics;
+pub mod icon;
+pub mod max_size;
+pub mod qr;
 pub mod request;
 pub mod response;
+pub mod sp;
+pub mod sysvar;
 
 use crc::{Crc, CRC_16_MODBUS};
 pub use error::{Error, Result};
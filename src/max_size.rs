@@ -0,0 +1,366 @@
+//! Maximum encoded size
+//!
+//! [`MaxSize`] gives the maximum number of bytes a type takes in a DGUS payload,
+//! usable for sizing the request and response buffers at compile time.
+//!
+//! # Example
+//!
+//! Derive it for your own types with the `derive` feature.
+//!
+//! ```rust
+//! # #[cfg(feature = "derive")] {
+//! use dguscard::{
+//!     command::{Read, Word, Write},
+//!     max_size::{body_size, frame_size, MaxSize},
+//!     request::Request,
+//!     response::Accumulator,
+//! };
+//!
+//! #[derive(serde::Serialize, serde::Deserialize, MaxSize)]
+//! struct MyData {
+//!     word: u16,
+//!     dword: u32,
+//!     flags: [bool; 2],
+//! }
+//! assert_eq!(MyData::MAX_SIZE, 10);
+//!
+//! // Header, length, command, address, payload and CRC
+//! const TX_LEN: usize = frame_size::<Word<Write>>(MyData::MAX_SIZE, true);
+//! assert_eq!(TX_LEN, 18);
+//! let buf = &mut [0u8; TX_LEN];
+//! let mut frame = Request::with_slice(buf, Word { addr: 0x1000, cmd: Write }).unwrap();
+//! frame.push(&MyData { word: 1, dword: 2, flags: [true, false] }).unwrap();
+//! assert_eq!(frame.finalize(true).unwrap().len(), TX_LEN);
+//!
+//! // The accumulator buffer must be larger than the length field of the response
+//! let rx: Accumulator<{ body_size::<Word<Read>>(MyData::MAX_SIZE, true) + 1 }> = Accumulator::new(true);
+//! # }
+//! ```
+
+use crate::{
+    color::Rgb565,
+    command::{Command, Curve, Dword, Read, Register, Word, Write},
+    curve::CurveBuffer,
+    encoding::{Be, ByteBool, Le, LenientBool, OnOff, Pad, WordSwapped},
+    fixed::Fixed,
+    flags::WordFlags,
+    graphics::{AreaCopy, Circle, Dot, FilledRect, Line, Point, Rect},
+    icon::IconId,
+    sp::{BasicGraphics, DataVariable, Icon, TextDisplay},
+    sysvar::{
+        Beep, DeviceId, DeviceInfo, Music, NorAccess, TouchAction, TouchEvent, TouchSimulation,
+        TouchStatus, Version,
+    },
+};
+use core::marker::PhantomData;
+
+/// Maximum number of bytes a type takes in a DGUS payload
+///
+/// Implemented for the primitives, arrays, tuples and the dguscard types.
+/// Derive it for your own structs and unit enums with the `derive` feature.
+pub trait MaxSize {
+    /// Maximum encoded size in bytes
+    const MAX_SIZE: usize;
+}
+
+/// Derives [`MaxSize`] as the sum of the field sizes, unit enums take a word.
+///
+/// Serde attributes may change the serialized form, so they are rejected.
+///
+/// ```compile_fail
+/// use dguscard::max_size::MaxSize;
+///
+/// #[derive(serde::Serialize, MaxSize)]
+/// struct MyData {
+///     #[serde(skip)]
+///     word: u16,
+/// }
+/// ```
+#[cfg(feature = "derive")]
+pub use dguscard_derive::MaxSize;
+
+/// Returns the value of the length field of a frame, i.e. the number of bytes after it.
+pub const fn body_size<C: Command + MaxSize>(payload: usize, crc: bool) -> usize {
+    1 + C::MAX_SIZE + payload + if crc { 2 } else { 0 }
+}
+
+/// Returns the total number of bytes of a frame, including header, length, command, address and CRC.
+pub const fn frame_size<C: Command + MaxSize>(payload: usize, crc: bool) -> usize {
+    3 + body_size::<C>(payload, crc)
+}
+
+macro_rules! impl_max_size_of {
+    ($($ty:ty)+) => ($(
+        impl MaxSize for $ty {
+            const MAX_SIZE: usize = core::mem::size_of::<$ty>();
+        }
+    )+)
+}
+
+impl_max_size_of! { u8 u16 u32 u64 u128 i8 i16 i32 i64 i128 f32 f64 }
+
+impl MaxSize for bool {
+    // Word encoded by default
+    const MAX_SIZE: usize = 2;
+}
+
+impl MaxSize for () {
+    const MAX_SIZE: usize = 0;
+}
+
+impl<T: ?Sized> MaxSize for PhantomData<T> {
+    const MAX_SIZE: usize = 0;
+}
+
+impl<T: MaxSize + ?Sized> MaxSize for &T {
+    const MAX_SIZE: usize = T::MAX_SIZE;
+}
+
+impl<T: MaxSize, const N: usize> MaxSize for [T; N] {
+    const MAX_SIZE: usize = T::MAX_SIZE * N;
+}
+
+macro_rules! impl_max_size_tuple {
+    ($($name:ident)+) => {
+        impl<$($name: MaxSize),+> MaxSize for ($($name,)+) {
+            const MAX_SIZE: usize = 0 $(+ $name::MAX_SIZE)+;
+        }
+    };
+}
+
+impl_max_size_tuple! { A }
+impl_max_size_tuple! { A B }
+impl_max_size_tuple! { A B C }
+impl_max_size_tuple! { A B C D }
+impl_max_size_tuple! { A B C D E }
+impl_max_size_tuple! { A B C D E F }
+impl_max_size_tuple! { A B C D E F G }
+impl_max_size_tuple! { A B C D E F G H }
+impl_max_size_tuple! { A B C D E F G H I }
+impl_max_size_tuple! { A B C D E F G H I J }
+impl_max_size_tuple! { A B C D E F G H I J K }
+impl_max_size_tuple! { A B C D E F G H I J K L }
+
+impl MaxSize for Pad {
+    const MAX_SIZE: usize = 1;
+}
+
+impl<T: MaxSize> MaxSize for Be<T> {
+    const MAX_SIZE: usize = T::MAX_SIZE;
+}
+
+impl<T: MaxSize> MaxSize for Le<T> {
+    const MAX_SIZE: usize = T::MAX_SIZE;
+}
+
+impl<T: MaxSize> MaxSize for WordSwapped<T> {
+    const MAX_SIZE: usize = T::MAX_SIZE;
+}
+
+impl MaxSize for ByteBool {
+    const MAX_SIZE: usize = 1;
+}
+
+impl MaxSize for LenientBool {
+    const MAX_SIZE: usize = 2;
+}
+
+impl<const ON: u16, const OFF: u16> MaxSize for OnOff<ON, OFF> {
+    const MAX_SIZE: usize = 2;
+}
+
+impl<const DECIMALS: u8, T: MaxSize> MaxSize for Fixed<DECIMALS, T> {
+    const MAX_SIZE: usize = T::MAX_SIZE;
+}
+
+// Sum of the field types
+macro_rules! impl_max_size {
+    ($ty:ty { $($field:ty),* $(,)? }) => {
+        impl MaxSize for $ty {
+            const MAX_SIZE: usize = 0 $(+ <$field as MaxSize>::MAX_SIZE)*;
+        }
+    };
+}
+
+impl_max_size! { Rgb565 { u16 } }
+impl_max_size! { WordFlags { u16 } }
+impl_max_size! { IconId { u16 } }
+impl_max_size! { CurveBuffer { u16, u16 } }
+
+impl_max_size! { Point { u16, u16 } }
+impl_max_size! { Dot { Point, Rgb565 } }
+impl_max_size! { Line { Point, Point, Rgb565 } }
+impl_max_size! { Rect { Point, Point, Rgb565 } }
+impl_max_size! { FilledRect { Point, Point, Rgb565 } }
+impl_max_size! { Circle { Point, u16, Rgb565 } }
+impl_max_size! { AreaCopy { u16, Point, Point, Point } }
+
+// Types spanning a fixed number of words
+macro_rules! impl_max_size_wlen {
+    ($($ty:ty)+) => ($(
+        impl MaxSize for $ty {
+            const MAX_SIZE: usize = <$ty>::WLEN as usize * 2;
+        }
+    )+)
+}
+
+impl_max_size_wlen! { DataVariable TextDisplay Icon BasicGraphics }
+impl_max_size_wlen! { Music Beep DeviceId Version DeviceInfo NorAccess TouchSimulation TouchStatus }
+
+impl MaxSize for TouchAction {
+    const MAX_SIZE: usize = 2;
+}
+
+impl MaxSize for TouchEvent {
+    const MAX_SIZE: usize = 1;
+}
+
+// Command data between the command code and the payload
+impl_max_size! { Write {} }
+impl_max_size! { Read { u8 } }
+impl_max_size! { Register<Write> { u8, u8 } }
+impl_max_size! { Register<Read> { u8, u8, Read } }
+impl_max_size! { Word<Write> { u16 } }
+impl_max_size! { Word<Read> { u16, Read } }
+impl_max_size! { Dword<Write> { u32 } }
+impl_max_size! { Dword<Read> { u32, Read } }
+impl_max_size! { Curve { u8 } }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_size() {
+        assert_eq!(0, <()>::MAX_SIZE);
+        assert_eq!(16, <(u8, Pad, u16, [i16; 2], Le<u64>)>::MAX_SIZE);
+        assert_eq!(8, <[Fixed<2, i32>; 2]>::MAX_SIZE);
+        assert_eq!(3, <(ByteBool, OnOff<1, 2>)>::MAX_SIZE);
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derive() {
+        #[derive(MaxSize)]
+        struct Named {
+            _a: u16,
+            _b: (u8, Pad),
+            _c: [Le<u32>; 2],
+        }
+        #[derive(MaxSize)]
+        #[allow(dead_code)]
+        struct Tuple<T>(u8, T);
+        #[derive(MaxSize)]
+        struct Unit;
+        #[derive(MaxSize)]
+        enum Mode {
+            _On,
+            _Off,
+        }
+
+        assert_eq!(12, Named::MAX_SIZE);
+        assert_eq!(5, Tuple::<f32>::MAX_SIZE);
+        assert_eq!(0, Unit::MAX_SIZE);
+        assert_eq!(2, Mode::MAX_SIZE);
+    }
+
+    /// Asserts that the serialized value takes exactly its maximum size.
+    fn assert_size<T: MaxSize + serde::Serialize>(value: T) {
+        let buf = &mut [0u8; 64];
+        let mut serializer = crate::request::Serializer::new(crate::request::Slice::new(buf));
+        value.serialize(&mut serializer).unwrap();
+        assert_eq!(T::MAX_SIZE, serializer.output.len());
+    }
+
+    #[test]
+    fn wire_types() {
+        let point = Point::new(1, 2);
+        let color = Rgb565::RED;
+        assert_size(CurveBuffer::default());
+        assert_size(point);
+        assert_size(Dot { at: point, color });
+        assert_size(Line {
+            start: point,
+            end: point,
+            color,
+        });
+        assert_size(Rect {
+            top_left: point,
+            bottom_right: point,
+            color,
+        });
+        assert_size(FilledRect {
+            top_left: point,
+            bottom_right: point,
+            color,
+        });
+        assert_size(Circle {
+            center: point,
+            radius: 3,
+            color,
+        });
+        assert_size(AreaCopy {
+            page: 1,
+            top_left: point,
+            bottom_right: point,
+            target: point,
+        });
+        assert_size(DataVariable {
+            vp: 0x5000,
+            position: point,
+            color,
+            font_lib: 0,
+            font_width: 0,
+            alignment: 0,
+            integer_digits: 0,
+            decimal_digits: 0,
+            data_type: 0,
+        });
+        assert_size(TextDisplay {
+            vp: 0x5000,
+            position: point,
+            color,
+            top_left: point,
+            bottom_right: point,
+            len: 0,
+            font0: 0,
+            font1: 0,
+            font_width: 0,
+            font_height: 0,
+            encoding: 0,
+            h_spacing: 0,
+            v_spacing: 0,
+        });
+        assert_size(Icon {
+            vp: 0x5000,
+            position: point,
+            value_min: 0,
+            value_max: 0,
+            icon_min: 0,
+            icon_max: 0,
+            icon_lib: 0,
+            mode: 0,
+        });
+        assert_size(BasicGraphics {
+            vp: 0x5000,
+            top_left: point,
+            bottom_right: point,
+        });
+        assert_size(Music::play(1, 1, 0x40).unwrap());
+        assert_size(Beep::ms(100).unwrap());
+        assert_size(NorAccess::read(0, 0x5000, 2).unwrap());
+        assert_size(TouchSimulation::press(1, 2));
+        assert_size(TouchAction::Hold);
+        assert_eq!(DeviceInfo::MAX_SIZE, 32);
+        assert_eq!(TouchStatus::MAX_SIZE, 6);
+    }
+
+    #[test]
+    fn frame() {
+        assert_eq!(6, frame_size::<Word<Write>>(0, false));
+        assert_eq!(9, frame_size::<Word<Read>>(0, true));
+        assert_eq!(5, body_size::<Dword<Write>>(0, false));
+        assert_eq!(4, body_size::<Register<Read>>(0, false));
+        assert_eq!(13, frame_size::<Curve>(8, false));
+    }
+}
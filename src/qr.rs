@@ -0,0 +1,166 @@
+//! QR code display helpers
+
+use crate::{
+    command::Write,
+    request::{Request, Storage, WordFrames},
+    Error, Result,
+};
+use serde::{ser::SerializeTuple, Serialize, Serializer};
+
+/// Configured size of a QR code display control
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum QrSize {
+    /// 45x45 modules
+    Small,
+    /// 73x73 modules
+    Large,
+}
+
+impl QrSize {
+    /// Returns the maximum payload length in bytes.
+    pub const fn max_len(self) -> usize {
+        match self {
+            QrSize::Small => 154,
+            QrSize::Large => 458,
+        }
+    }
+}
+
+/// QR code payload
+///
+/// Serializes into the payload bytes followed by a `0xFFFF` terminator,
+/// padded with `0xFF` to the word boundary.
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::{qr::{QrCode, QrSize}, request::Slice};
+///
+/// let qr = QrCode::new(b"https://a.io", QrSize::Small).unwrap();
+///
+/// let buf = &mut [0u8; 32];
+/// let frame = qr.request(0x7000, Slice::new(buf)).unwrap().finalize(false).unwrap();
+/// assert_eq!(&frame[..6], &[0x5A, 0xA5, 17, 0x82, 0x70, 0x00]);
+/// assert_eq!(&frame[6..], b"https://a.io\xFF\xFF");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct QrCode<'a> {
+    payload: &'a [u8],
+}
+
+impl<'a> QrCode<'a> {
+    /// Terminator word
+    const END: u16 = 0xFFFF;
+
+    /// Returns a new QR code payload for a control of the given size.
+    ///
+    /// Fails with [`Error::RequestBadParam`] if the payload is empty or longer than [`QrSize::max_len`].
+    pub const fn new(payload: &'a [u8], size: QrSize) -> Result<Self> {
+        if payload.is_empty() || payload.len() > size.max_len() {
+            return Err(Error::RequestBadParam);
+        }
+        Ok(Self { payload })
+    }
+
+    /// Returns the payload.
+    pub const fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    /// Returns a new write request containing the payload written to the VP block at `vp`.
+    pub fn request<S: Storage>(&self, vp: u16, output: S) -> Result<Request<Write, S>> {
+        Request::word(output, vp, self)
+    }
+
+    /// Returns an iterator over the finalized frames, splitting the payload written to the VP block at `vp`
+    /// so that no frame exceeds `max_frame_len` bytes.
+    ///
+    /// Each frame continues at the address where the previous one left off.
+    pub fn frames<S: Storage + Default>(
+        &self,
+        vp: u16,
+        max_frame_len: usize,
+        crc: bool,
+    ) -> WordFrames<'_, Self, S> {
+        WordFrames::new(self, vp, max_frame_len, crc)
+    }
+}
+
+impl Serialize for QrCode<'_> {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        struct Bytes<'a>(&'a [u8]);
+
+        impl Serialize for Bytes<'_> {
+            fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_bytes(self.0)
+            }
+        }
+
+        let odd = self.payload.len() % 2 != 0;
+        let mut tuple = serializer.serialize_tuple(2 + odd as usize)?;
+        tuple.serialize_element(&Bytes(self.payload))?;
+        tuple.serialize_element(&Self::END)?;
+        if odd {
+            tuple.serialize_element(&0xFFu8)?;
+        }
+        tuple.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::Slice;
+
+    #[test]
+    fn odd() {
+        let qr = QrCode::new(b"abc", QrSize::Small).unwrap();
+        let buf = &mut [0u8; 16];
+        let frame = qr
+            .request(0x7000, Slice::new(buf))
+            .unwrap()
+            .finalize(false)
+            .unwrap();
+        assert_eq!(
+            frame,
+            &[0x5A, 0xA5, 9, 0x82, 0x70, 0x00, b'a', b'b', b'c', 0xFF, 0xFF, 0xFF]
+        );
+    }
+
+    #[test]
+    fn bad_len() {
+        assert_eq!(Err(Error::RequestBadParam), QrCode::new(b"", QrSize::Small));
+        let payload = [b'a'; 155];
+        assert_eq!(
+            Err(Error::RequestBadParam),
+            QrCode::new(&payload, QrSize::Small)
+        );
+        assert!(QrCode::new(&payload, QrSize::Large).is_ok());
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn frames() {
+        use crate::request::HVec;
+        let qr = QrCode::new(b"abcdef", QrSize::Small).unwrap();
+        // 3 words per frame
+        let mut frames = qr.frames::<HVec<16>>(0x7000, 12, false);
+        assert_eq!(
+            &frames.next().unwrap().unwrap(),
+            &[0x5A, 0xA5, 9, 0x82, 0x70, 0x00, b'a', b'b', b'c', b'd', b'e', b'f']
+        );
+        assert_eq!(
+            &frames.next().unwrap().unwrap(),
+            &[0x5A, 0xA5, 5, 0x82, 0x70, 0x03, 0xFF, 0xFF]
+        );
+        assert!(frames.next().is_none());
+    }
+}
@@ -1,17 +1,20 @@
 //! Request builder
 
 mod serializer;
+mod split;
 mod storage;
 
-pub use self::storage::{Slice, Storage};
+pub use self::split::{FrameSource, Frames, WordFrames, WordSplit};
+pub use self::storage::{Checkpoint, Slice, Storage};
 
 #[cfg(feature = "heapless")]
 pub use self::storage::HVec;
 
-use self::serializer::Serializer;
+pub(crate) use self::serializer::Serializer;
 use crate::{
-    command::{Command, Write},
-    Result, CRC, HEADER,
+    command::{Command, Word, Write},
+    encoding::{BoolEncoding, Endian},
+    Error, Result, CRC, HEADER,
 };
 use core::marker::PhantomData;
 use serde::Serialize;
@@ -59,9 +62,29 @@ use serde::Serialize;
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Request<C, S: Storage> {
     serializer: Serializer<S>,
+    align: Align,
+    max_len: u8,
     cmd: PhantomData<C>,
 }
 
+/// Protocol limit of the bytes after the length field, i.e. command, address, payload and CRC
+pub const MAX_LEN: u8 = u8::MAX;
+
+/// Word alignment check performed by [`Request::finalize`]
+///
+/// T5L is a word machine, a payload ending on a half word leaves the last word partially written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Align {
+    /// Finalize the payload as is
+    #[default]
+    Ignore,
+    /// Pad the payload with a zero byte if it ends on a half word
+    Pad,
+    /// Fail with [`Error::RequestMisaligned`] if the payload ends on a half word
+    Strict,
+}
+
 impl<'a, C> Request<C, Slice<'a>> {
     /// Returns a new builder that uses a [`Slice`] as a given backing buffer.
     /// The request will be finalized as [`u8`] slice.
@@ -83,10 +106,124 @@ impl<S, O> Request<Write, S>
 where
     S: Storage<Output = O>,
 {
+    /// Returns a new [`Word<Write>`] request containing `value` written to `addr`.
+    pub(crate) fn word<T: Serialize + ?Sized>(output: S, addr: u16, value: &T) -> Result<Self> {
+        let mut request = Self::new(output, Word { addr, cmd: Write })?;
+        request.push(value)?;
+        Ok(request)
+    }
+
     /// Appends a `T` into the [`Request<Write, S>`].
+    ///
+    /// Bytes past the limit set by [`Request::with_max_len`] are never stored, failing with [`Error::RequestTooLarge`].
+    /// The part of the value within the limit is left written, see [`Request::try_push`] to discard it.
     pub fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
         value.serialize(&mut self.serializer)
     }
+
+    /// Appends a `T` into the [`Request<Write, S>`], or nothing if it fails.
+    ///
+    /// Unlike [`Request::push`], a value that doesn't fit, e.g. [`Error::RequestBufferFull`],
+    /// is rolled back instead of being left half written.
+    /// Fails with [`Error::NotYetImplemented`] instead if the storage doesn't support [`Storage::rollback`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use dguscard::{request::Request, command::{Word, Write}, Error};
+    ///
+    /// let buf = &mut [0u8; 10];
+    /// let mut frame = Request::with_slice(buf, Word { addr: 0x1000, cmd: Write }).unwrap();
+    /// frame.try_push(&0x1234u16).unwrap();
+    /// assert_eq!(frame.remaining_capacity(), 2);
+    /// assert_eq!(frame.try_push(&0x5678_9ABCu32), Err(Error::RequestBufferFull));
+    /// assert_eq!(frame.remaining_capacity(), 2);
+    /// frame.try_push(&0x5678u16).unwrap();
+    /// let tx_bytes = frame.finalize(false).unwrap();
+    /// assert_eq!(tx_bytes, &[0x5A, 0xA5, 7, 0x82, 0x10, 0x00, 0x12, 0x34, 0x56, 0x78]);
+    /// ```
+    pub fn try_push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let checkpoint = self.serializer.output.checkpoint();
+        let pos = self.serializer.pos;
+        value.serialize(&mut self.serializer).or_else(|e| {
+            self.serializer.output.rollback(checkpoint)?;
+            self.serializer.pos = pos;
+            Err(e)
+        })
+    }
+
+    /// Appends every item of an iterator into the [`Request<Write, S>`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use dguscard::{request::Request, command::{Word, Write}};
+    ///
+    /// let buf = &mut [0u8; 16];
+    /// let mut frame = Request::with_slice(buf, Word { addr: 0x1000, cmd: Write }).unwrap();
+    /// frame.push_iter((1u16..=3).map(|v| v * 10)).unwrap();
+    /// let tx_bytes = frame.finalize(false).unwrap();
+    /// assert_eq!(tx_bytes, &[0x5A, 0xA5, 9, 0x82, 0x10, 0x00, 0, 10, 0, 20, 0, 30]);
+    /// ```
+    pub fn push_iter<I>(&mut self, iter: I) -> Result<()>
+    where
+        I: IntoIterator,
+        I::Item: Serialize,
+    {
+        iter.into_iter()
+            .try_for_each(|value| value.serialize(&mut self.serializer))
+    }
+
+    /// Returns a text writer appending formatted text into the [`Request<Write, S>`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use core::fmt::Write as _;
+    /// use dguscard::{request::Request, command::{Word, Write}};
+    ///
+    /// let buf = &mut [0u8; 16];
+    /// let mut frame = Request::with_slice(buf, Word { addr: 0x1000, cmd: Write }).unwrap();
+    /// let t = 25;
+    /// write!(frame.text(), "T={}C", t).unwrap();
+    /// frame.text().terminate().unwrap();
+    /// let tx_bytes = frame.finalize(false).unwrap();
+    /// assert_eq!(tx_bytes, b"\x5A\xA5\x0A\x82\x10\x00T=25C\xFF\xFF");
+    /// ```
+    pub fn text(&mut self) -> Text<'_, S> {
+        Text {
+            serializer: &mut self.serializer,
+        }
+    }
+
+    /// Pads the payload with a zero byte if it ends on a half word.
+    pub fn align_to_word(&mut self) -> Result<()> {
+        self.serializer.align_to_word()
+    }
+}
+
+/// Text writer returned by [`Request::text`]
+///
+/// Implements [`core::fmt::Write`], writing the text as UTF-8 bytes without a terminator.
+/// Writing fails with [`core::fmt::Error`] if the request buffer is full
+/// or the text exceeds the length limit set by [`Request::with_max_len`].
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Text<'a, S: Storage> {
+    serializer: &'a mut Serializer<S>,
+}
+
+impl<S: Storage> Text<'_, S> {
+    /// Appends the 0xFFFF text terminator.
+    pub fn terminate(self) -> Result<()> {
+        0xFFFFu16.serialize(self.serializer)
+    }
+}
+
+impl<S: Storage> core::fmt::Write for Text<'_, S> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.serializer.write_str(s)
+    }
 }
 
 impl<RW, S, O> Request<RW, S>
@@ -97,7 +234,7 @@ where
     /// The request will be finalized as the given output type.
     /// It should rarely be necessary to directly use this function unless you implemented your own [`Storage`].
     pub fn new<C: Command>(output: S, cmd: C) -> Result<Self> {
-        let mut serializer = Serializer { output };
+        let mut serializer = Serializer::new(output);
         // Push header
         HEADER.serialize(&mut serializer)?;
         // Push length placeholder
@@ -106,20 +243,90 @@ where
         C::CMD.serialize(&mut serializer)?;
         // Push command data
         cmd.serialize(&mut serializer)?;
+        // Payload starts here
+        serializer.pos = 0;
+        serializer.limit = 3 + MAX_LEN as usize;
         // Return the builder
         Ok(Self {
             serializer,
+            align: Align::default(),
+            max_len: MAX_LEN,
             cmd: PhantomData,
         })
     }
 
+    /// Sets the word alignment check performed while finalizing, [`Align::Ignore`] by default.
+    pub fn with_align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Sets the byte order of the numbers in the payload, big-endian by default.
+    ///
+    /// Single values can override it with the [`Be`][crate::encoding::Be], [`Le`][crate::encoding::Le]
+    /// and [`WordSwapped`][crate::encoding::WordSwapped] wrappers.
+    pub fn with_endian(mut self, endian: Endian) -> Self {
+        self.serializer.endian = endian;
+        self
+    }
+
+    /// Sets the encoding of the `bool` values in the payload, [`BoolEncoding::Word`] by default.
+    pub fn with_bool_encoding(mut self, bools: BoolEncoding) -> Self {
+        self.serializer.bools = bools;
+        self
+    }
+
+    /// Limits the bytes after the length field, e.g. to the UART buffer of the panel, [`MAX_LEN`] by default.
+    ///
+    /// Pushing or finalizing a longer request fails with [`Error::RequestTooLarge`].
+    pub fn with_max_len(mut self, max_len: u8) -> Self {
+        self.max_len = max_len;
+        self.serializer.limit = 3 + max_len as usize;
+        self
+    }
+
+    /// Returns the number of bytes that can still be appended.
+    ///
+    /// The CRC isn't reserved, leave 2 bytes free to finalize with CRC.
+    pub fn remaining_capacity(&self) -> usize {
+        let len = (self.max_len as usize).saturating_sub(self.len());
+        self.serializer.output.remaining_capacity().min(len)
+    }
+
+    /// Returns the number of bytes after the length field.
+    fn len(&self) -> usize {
+        self.serializer.output.len() - 3
+    }
+
+    /// Fails if the bytes after the length field and the given extra bytes exceed the limit.
+    fn check_len(&self, extra: usize) -> Result<()> {
+        if self.len() + extra > self.max_len as usize {
+            return Err(Error::RequestTooLarge);
+        }
+        Ok(())
+    }
+
+    /// Returns true if the payload ends on a word boundary.
+    pub fn is_word_aligned(&self) -> bool {
+        self.serializer.is_word_aligned()
+    }
+
     /// Finalizes the request with optional CRC and returns the output.
+    ///
+    /// The payload is checked for word alignment as set by [`Request::with_align`].
     pub fn finalize(mut self, crc: bool) -> Result<O> {
+        match self.align {
+            Align::Ignore => (),
+            Align::Pad => self.serializer.align_to_word()?,
+            Align::Strict if !self.is_word_aligned() => return Err(Error::RequestMisaligned),
+            Align::Strict => (),
+        }
+        self.check_len(if crc { 2 } else { 0 })?;
         if crc {
-            let crc = CRC.checksum(&self.serializer.output[3..]).swap_bytes();
-            crc.serialize(&mut self.serializer)?;
+            let crc = CRC.checksum(&self.serializer.output[3..]);
+            self.serializer.output.try_extend(&crc.to_le_bytes())?;
         }
-        self.serializer.output[2] = self.serializer.output.len() as u8 - 3;
+        self.serializer.output[2] = self.len() as u8;
         Ok(self.serializer.output.finalize())
     }
 }
@@ -213,4 +420,186 @@ mod tests {
         let output: Vec<u8, 10> = frame.finalize(false).unwrap();
         assert_eq!(output, expected);
     }
+
+    #[test]
+    fn align_pad() {
+        let buf = &mut [0u8; 20];
+        let mut frame = Request::with_slice(
+            buf,
+            Word {
+                addr: 0x00DE,
+                cmd: Write,
+            },
+        )
+        .unwrap()
+        .with_align(Align::Pad);
+        frame.push(&0x12u8).unwrap();
+        assert!(!frame.is_word_aligned());
+        let output = frame.finalize(false).unwrap();
+        assert_eq!(output, &[0x5A, 0xA5, 5, 0x82, 0x00, 0xDE, 0x12, 0x00]);
+    }
+
+    #[test]
+    fn align_strict() {
+        let buf = &mut [0u8; 20];
+        let mut frame = Request::with_slice(
+            buf,
+            Word {
+                addr: 0x00DE,
+                cmd: Write,
+            },
+        )
+        .unwrap()
+        .with_align(Align::Strict);
+        frame.push(&0x12u8).unwrap();
+        assert_eq!(Err(Error::RequestMisaligned), frame.finalize(false));
+    }
+
+    #[test]
+    fn align_curve() {
+        use crate::command::Curve;
+        // The payload starts on an odd byte of the frame
+        let buf = &mut [0u8; 20];
+        let mut frame = Request::with_slice(buf, Curve { ch: 0b1 })
+            .unwrap()
+            .with_align(Align::Strict);
+        frame.push(&0x1234u16).unwrap();
+        let output = frame.finalize(false).unwrap();
+        assert_eq!(output, &[0x5A, 0xA5, 4, 0x84, 0x01, 0x12, 0x34]);
+    }
+
+    #[test]
+    fn try_push_rollback() {
+        let mut frame = Request::<_, HVec<10>>::with_hvec(Word {
+            addr: 0x00DE,
+            cmd: Write,
+        })
+        .unwrap();
+        frame.try_push(&0x12u8).unwrap();
+        assert_eq!(
+            Err(Error::RequestBufferFull),
+            frame.try_push(&(0x34u8, 0x5678u16, 0x9Au8))
+        );
+        assert_eq!(3, frame.remaining_capacity());
+        assert!(!frame.is_word_aligned());
+        frame.try_push(&0x34u8).unwrap();
+        assert!(frame.is_word_aligned());
+        let output = frame.finalize(false).unwrap();
+        assert_eq!(&output, &[0x5A, 0xA5, 5, 0x82, 0x00, 0xDE, 0x12, 0x34]);
+    }
+
+    #[test]
+    fn storage_defaults() {
+        // A storage implementing only the required methods
+        #[derive(Default)]
+        struct Minimal(Vec<u8, 16>);
+
+        impl core::ops::Deref for Minimal {
+            type Target = [u8];
+            fn deref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl core::ops::DerefMut for Minimal {
+            fn deref_mut(&mut self) -> &mut [u8] {
+                &mut self.0
+            }
+        }
+
+        impl Storage for Minimal {
+            type Output = Vec<u8, 16>;
+            fn try_push(&mut self, data: u8) -> Result<()> {
+                self.0.push(data).map_err(|_| Error::RequestBufferFull)
+            }
+            fn finalize(self) -> Self::Output {
+                self.0
+            }
+        }
+
+        let mut frame = Request::new(
+            Minimal::default(),
+            Word {
+                addr: 0x00DE,
+                cmd: Write,
+            },
+        )
+        .unwrap();
+        assert_eq!(MAX_LEN as usize - 3, frame.remaining_capacity());
+        frame.try_push(&0x1234u16).unwrap();
+        assert_eq!(MAX_LEN as usize - 5, frame.remaining_capacity());
+        // The failed push can't be rolled back
+        assert_eq!(Err(Error::NotYetImplemented), frame.try_push(&[0u8; 16]));
+    }
+
+    #[test]
+    fn max_len() {
+        let mut frame = Request::<_, HVec<300>>::with_hvec(Word {
+            addr: 0x00DE,
+            cmd: Write,
+        })
+        .unwrap()
+        .with_max_len(9);
+        assert_eq!(6, frame.remaining_capacity());
+        assert_eq!(Err(Error::RequestTooLarge), frame.try_push(&[0u16; 4]));
+        frame.try_push(&[0u16; 3]).unwrap();
+        assert_eq!(0, frame.remaining_capacity());
+        assert_eq!(Err(Error::RequestTooLarge), frame.finalize(true));
+
+        let mut frame = Request::<_, HVec<300>>::with_hvec(Word {
+            addr: 0x00DE,
+            cmd: Write,
+        })
+        .unwrap();
+        frame.push(&[[0u8; 32]; 7]).unwrap();
+        frame.push(&[0u8; 26]).unwrap();
+        assert_eq!(2, frame.remaining_capacity());
+        // Only the bytes within the limit are stored
+        assert_eq!(Err(Error::RequestTooLarge), frame.push(&[0u8; 4]));
+        assert_eq!(0, frame.remaining_capacity());
+        assert_eq!(Err(Error::RequestTooLarge), frame.push(&0u8));
+        assert_eq!(Err(Error::RequestTooLarge), frame.push_iter([0u8]));
+        let output = frame.finalize(false).unwrap();
+        assert_eq!(output.len(), 258);
+        assert_eq!(output[2], 255);
+    }
+
+    #[test]
+    fn remaining_capacity_crc() {
+        let mut frame = Request::<_, HVec<16>>::with_hvec(Word {
+            addr: 0x00DE,
+            cmd: Write,
+        })
+        .unwrap();
+        assert_eq!(10, frame.remaining_capacity());
+        // Leave room for the CRC
+        frame.push(&[0u8; 8]).unwrap();
+        assert_eq!(2, frame.remaining_capacity());
+        assert_eq!(frame.finalize(true).unwrap().len(), 16);
+
+        let mut frame = Request::<_, HVec<16>>::with_hvec(Word {
+            addr: 0x00DE,
+            cmd: Write,
+        })
+        .unwrap();
+        frame.push(&[0u8; 10]).unwrap();
+        assert_eq!(0, frame.remaining_capacity());
+        assert_eq!(Err(Error::RequestBufferFull), frame.finalize(true));
+    }
+
+    #[test]
+    fn text_max_len() {
+        use core::fmt::Write as _;
+        let mut frame = Request::<_, HVec<300>>::with_hvec(Word {
+            addr: 0x00DE,
+            cmd: Write,
+        })
+        .unwrap()
+        .with_max_len(7);
+        write!(frame.text(), "T={}", 25).unwrap();
+        assert!(write!(frame.text(), "C").is_err());
+        assert_eq!(Err(Error::RequestTooLarge), frame.text().terminate());
+        let output = frame.finalize(false).unwrap();
+        assert_eq!(&output, b"\x5A\xA5\x07\x82\x00\xDET=25");
+    }
 }
@@ -1,5 +1,8 @@
 use super::Storage;
-use crate::{Error, Result};
+use crate::{
+    encoding::{BoolEncoding, Endian, BE, LE, PAD, WORD_SWAPPED},
+    Error, Result,
+};
 use serde::{ser, Serialize};
 
 /// `serde` compatible serializer.
@@ -11,6 +14,69 @@ pub struct Serializer<S: Storage> {
     /// This is the Storage that will be used to store any bytes generated
     /// by serialization
     pub output: S,
+    /// Number of bytes serialized since the start of the first word
+    pub pos: usize,
+    /// Byte order of the numbers
+    pub endian: Endian,
+    /// Encoding of the `bool` values
+    pub bools: BoolEncoding,
+    /// Maximum length of the output, exceeding it fails with [`Error::RequestTooLarge`]
+    pub limit: usize,
+}
+
+impl<S: Storage> Serializer<S> {
+    /// Returns a new serializer with the first word starting at the current position.
+    pub fn new(output: S) -> Self {
+        Self {
+            output,
+            pos: 0,
+            endian: Endian::Big,
+            bools: BoolEncoding::Word,
+            limit: usize::MAX,
+        }
+    }
+
+    /// Returns true if the serialized bytes end on a word boundary.
+    pub fn is_word_aligned(&self) -> bool {
+        self.pos % 2 == 0
+    }
+
+    /// Pads with a zero byte if the serialized bytes end on a half word.
+    pub fn align_to_word(&mut self) -> Result<()> {
+        if self.is_word_aligned() {
+            Ok(())
+        } else {
+            self.try_extend(&[0])
+        }
+    }
+
+    #[inline]
+    fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+        if self.output.len() + data.len() > self.limit {
+            return Err(Error::RequestTooLarge);
+        }
+        self.output.try_extend(data)?;
+        self.pos += data.len();
+        Ok(())
+    }
+
+    /// Reorders the big-endian bytes of a number to the current byte order and extends the output.
+    #[inline]
+    fn try_extend_endian<const N: usize>(&mut self, mut bytes: [u8; N]) -> Result<()> {
+        self.endian.reorder(&mut bytes);
+        self.try_extend(&bytes)
+    }
+
+    /// Serializes a value with the given byte order, restoring the current one afterwards.
+    fn with_endian<T>(&mut self, endian: Endian, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let prev = core::mem::replace(&mut self.endian, endian);
+        let result = value.serialize(&mut *self);
+        self.endian = prev;
+        result
+    }
 }
 
 impl<S: Storage> ser::Serializer for &'_ mut Serializer<S> {
@@ -33,67 +99,71 @@ impl<S: Storage> ser::Serializer for &'_ mut Serializer<S> {
 
     #[inline]
     fn serialize_bool(self, v: bool) -> Result<()> {
-        self.serialize_u16(if v { 1 } else { 0 })
+        match self.bools {
+            BoolEncoding::Word | BoolEncoding::LenientWord => self.serialize_u16(v.into()),
+            BoolEncoding::Byte | BoolEncoding::LenientByte => self.serialize_u8(v.into()),
+            BoolEncoding::Custom { on, off } => self.serialize_u16(if v { on } else { off }),
+        }
     }
 
     #[inline]
     fn serialize_i8(self, v: i8) -> Result<()> {
-        self.output.try_extend(&v.to_be_bytes())
+        self.try_extend_endian(v.to_be_bytes())
     }
 
     #[inline]
     fn serialize_i16(self, v: i16) -> Result<()> {
-        self.output.try_extend(&v.to_be_bytes())
+        self.try_extend_endian(v.to_be_bytes())
     }
 
     #[inline]
     fn serialize_i32(self, v: i32) -> Result<()> {
-        self.output.try_extend(&v.to_be_bytes())
+        self.try_extend_endian(v.to_be_bytes())
     }
 
     #[inline]
     fn serialize_i64(self, v: i64) -> Result<()> {
-        self.output.try_extend(&v.to_be_bytes())
+        self.try_extend_endian(v.to_be_bytes())
     }
 
     #[inline]
     fn serialize_i128(self, v: i128) -> Result<()> {
-        self.output.try_extend(&v.to_be_bytes())
+        self.try_extend_endian(v.to_be_bytes())
     }
 
     #[inline]
     fn serialize_u8(self, v: u8) -> Result<()> {
-        self.output.try_extend(&v.to_be_bytes())
+        self.try_extend_endian(v.to_be_bytes())
     }
 
     #[inline]
     fn serialize_u16(self, v: u16) -> Result<()> {
-        self.output.try_extend(&v.to_be_bytes())
+        self.try_extend_endian(v.to_be_bytes())
     }
 
     #[inline]
     fn serialize_u32(self, v: u32) -> Result<()> {
-        self.output.try_extend(&v.to_be_bytes())
+        self.try_extend_endian(v.to_be_bytes())
     }
 
     #[inline]
     fn serialize_u64(self, v: u64) -> Result<()> {
-        self.output.try_extend(&v.to_be_bytes())
+        self.try_extend_endian(v.to_be_bytes())
     }
 
     #[inline]
     fn serialize_u128(self, v: u128) -> Result<()> {
-        self.output.try_extend(&v.to_be_bytes())
+        self.try_extend_endian(v.to_be_bytes())
     }
 
     #[inline]
     fn serialize_f32(self, v: f32) -> Result<()> {
-        self.output.try_extend(&v.to_be_bytes())
+        self.try_extend_endian(v.to_be_bytes())
     }
 
     #[inline]
     fn serialize_f64(self, v: f64) -> Result<()> {
-        self.output.try_extend(&v.to_be_bytes())
+        self.try_extend_endian(v.to_be_bytes())
     }
 
     #[inline]
@@ -102,13 +172,15 @@ impl<S: Storage> ser::Serializer for &'_ mut Serializer<S> {
     }
 
     #[inline]
-    fn serialize_str(self, _v: &str) -> Result<()> {
-        Err(Error::NotYetImplemented)
+    fn serialize_str(self, v: &str) -> Result<()> {
+        // Text followed by the 0xFFFF terminator
+        self.try_extend(v.as_bytes())?;
+        self.try_extend(&[0xFF, 0xFF])
     }
 
     #[inline]
     fn serialize_bytes(self, v: &[u8]) -> Result<()> {
-        self.output.try_extend(v)
+        self.try_extend(v)
     }
 
     #[inline]
@@ -130,8 +202,12 @@ impl<S: Storage> ser::Serializer for &'_ mut Serializer<S> {
     }
 
     #[inline]
-    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
-        Ok(())
+    fn serialize_unit_struct(self, name: &'static str) -> Result<()> {
+        if name == PAD {
+            self.align_to_word()
+        } else {
+            Ok(())
+        }
     }
 
     #[inline]
@@ -149,11 +225,16 @@ impl<S: Storage> ser::Serializer for &'_ mut Serializer<S> {
     }
 
     #[inline]
-    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<()>
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
-        value.serialize(self)
+        match name {
+            BE => self.with_endian(Endian::Big, value),
+            LE => self.with_endian(Endian::Little, value),
+            WORD_SWAPPED => self.with_endian(Endian::WordSwapped, value),
+            _ => value.serialize(self),
+        }
     }
 
     #[inline]
@@ -222,11 +303,20 @@ impl<S: Storage> ser::Serializer for &'_ mut Serializer<S> {
     }
 
     #[inline]
-    fn collect_str<T>(self, _value: &T) -> Result<()>
+    fn collect_str<T>(self, value: &T) -> Result<()>
     where
         T: core::fmt::Display + ?Sized,
     {
-        Err(Error::NotYetImplemented)
+        // Formatted text followed by the 0xFFFF terminator
+        core::fmt::write(self, format_args!("{value}")).map_err(|_| Error::RequestBufferFull)?;
+        self.try_extend(&[0xFF, 0xFF])
+    }
+}
+
+/// Writes the text as is, fails only if the output is full.
+impl<S: Storage> core::fmt::Write for Serializer<S> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.try_extend(s.as_bytes()).map_err(|_| core::fmt::Error)
     }
 }
 
@@ -374,9 +464,7 @@ mod tests {
     #[test]
     fn u8_single() {
         let buf = &mut [0xCDu8; 1];
-        let mut ser = Serializer {
-            output: Slice::new(buf),
-        };
+        let mut ser = Serializer::new(Slice::new(buf));
         0x12u8.serialize(&mut ser).unwrap();
         assert_eq!(&[0x12], ser.output.finalize());
     }
@@ -384,9 +472,7 @@ mod tests {
     #[test]
     fn u16_single() {
         let buf = &mut [0xCDu8; 2];
-        let mut ser = Serializer {
-            output: Slice::new(buf),
-        };
+        let mut ser = Serializer::new(Slice::new(buf));
         0x1234u16.serialize(&mut ser).unwrap();
         assert_eq!(&[0x12, 0x34], ser.output.finalize());
     }
@@ -394,9 +480,7 @@ mod tests {
     #[test]
     fn u32_single() {
         let buf = &mut [0xCDu8; 4];
-        let mut ser = Serializer {
-            output: Slice::new(buf),
-        };
+        let mut ser = Serializer::new(Slice::new(buf));
         0x12345678u32.serialize(&mut ser).unwrap();
         assert_eq!(&[0x12, 0x34, 0x56, 0x78], ser.output.finalize());
     }
@@ -404,9 +488,7 @@ mod tests {
     #[test]
     fn u64_single() {
         let buf = &mut [0xCDu8; 8];
-        let mut ser = Serializer {
-            output: Slice::new(buf),
-        };
+        let mut ser = Serializer::new(Slice::new(buf));
         0x1234567890ABCDEFu64.serialize(&mut ser).unwrap();
         assert_eq!(
             &[0x12, 0x34, 0x56, 0x78, 0x90, 0xAB, 0xCD, 0xEF],
@@ -417,9 +499,7 @@ mod tests {
     #[test]
     fn u128_single() {
         let buf = &mut [0xCDu8; 16];
-        let mut ser = Serializer {
-            output: Slice::new(buf),
-        };
+        let mut ser = Serializer::new(Slice::new(buf));
         0x1234567890ABCDEFFEDCBA0987654321u128
             .serialize(&mut ser)
             .unwrap();
@@ -435,9 +515,7 @@ mod tests {
     #[test]
     fn unsigned_tuple() {
         let buf = &mut [0xCDu8; 31];
-        let mut ser = Serializer {
-            output: Slice::new(buf),
-        };
+        let mut ser = Serializer::new(Slice::new(buf));
         (
             0x12u8,
             0x1234u16,
@@ -460,9 +538,7 @@ mod tests {
     #[test]
     fn u8_array() {
         let buf = &mut [0xCDu8; 4];
-        let mut ser = Serializer {
-            output: Slice::new(buf),
-        };
+        let mut ser = Serializer::new(Slice::new(buf));
         [0xDEu8, 0xAD, 0xBE, 0xEF].serialize(&mut ser).unwrap();
         assert_eq!(&[0xDE, 0xAD, 0xBE, 0xEF], ser.output.finalize());
     }
@@ -470,9 +546,7 @@ mod tests {
     #[test]
     fn u16_array() {
         let buf = &mut [0xCDu8; 4];
-        let mut ser = Serializer {
-            output: Slice::new(buf),
-        };
+        let mut ser = Serializer::new(Slice::new(buf));
         [0xDEADu16, 0xBEEF].serialize(&mut ser).unwrap();
         assert_eq!(&[0xDE, 0xAD, 0xBE, 0xEF], ser.output.finalize());
     }
@@ -480,9 +554,7 @@ mod tests {
     #[test]
     fn u32_array() {
         let buf = &mut [0xCDu8; 8];
-        let mut ser = Serializer {
-            output: Slice::new(buf),
-        };
+        let mut ser = Serializer::new(Slice::new(buf));
         [0xDEADBEEFu32, 0x12345678].serialize(&mut ser).unwrap();
         assert_eq!(
             &[0xDE, 0xAD, 0xBE, 0xEF, 0x12, 0x34, 0x56, 0x78],
@@ -493,9 +565,7 @@ mod tests {
     #[test]
     fn u64_array() {
         let buf = &mut [0xCDu8; 16];
-        let mut ser = Serializer {
-            output: Slice::new(buf),
-        };
+        let mut ser = Serializer::new(Slice::new(buf));
         [0xDEADBEEF12345678u64, 0xABCDEF0011223344u64]
             .serialize(&mut ser)
             .unwrap();
@@ -511,9 +581,7 @@ mod tests {
     #[test]
     fn u128_array() {
         let buf = &mut [0xCDu8; 32];
-        let mut ser = Serializer {
-            output: Slice::new(buf),
-        };
+        let mut ser = Serializer::new(Slice::new(buf));
         [
             0xDEADBEEF12345678ABCDEF0011223344u128,
             0xABCDEF0011223344DEADBEEF12345678,
@@ -533,9 +601,7 @@ mod tests {
     #[test]
     fn bool_true() {
         let buf = &mut [0xCDu8; 2];
-        let mut ser = Serializer {
-            output: Slice::new(buf),
-        };
+        let mut ser = Serializer::new(Slice::new(buf));
         true.serialize(&mut ser).unwrap();
         assert_eq!(&[0x00, 0x01], ser.output.finalize());
     }
@@ -543,9 +609,7 @@ mod tests {
     #[test]
     fn bool_false() {
         let buf = &mut [0xCDu8; 2];
-        let mut ser = Serializer {
-            output: Slice::new(buf),
-        };
+        let mut ser = Serializer::new(Slice::new(buf));
         false.serialize(&mut ser).unwrap();
         assert_eq!(&[0x00, 0x00], ser.output.finalize());
     }
@@ -553,9 +617,7 @@ mod tests {
     #[test]
     fn unit_variant() {
         let buf = &mut [0xCDu8; 2];
-        let mut ser = Serializer {
-            output: Slice::new(buf),
-        };
+        let mut ser = Serializer::new(Slice::new(buf));
 
         #[derive(Serialize, Debug, PartialEq)]
         enum Test {
@@ -567,4 +629,76 @@ mod tests {
         Test::Two.serialize(&mut ser).unwrap();
         assert_eq!(&[0x00, 0x02], ser.output.finalize());
     }
+
+    #[test]
+    fn pad() {
+        use crate::encoding::Pad;
+        let buf = &mut [0xCDu8; 6];
+        let mut ser = Serializer::new(Slice::new(buf));
+        (0x12u8, Pad, 0x3456u16, Pad, 0x78u8, 0x9Au8, Pad)
+            .serialize(&mut ser)
+            .unwrap();
+        assert!(ser.is_word_aligned());
+        assert_eq!(&[0x12, 0x00, 0x34, 0x56, 0x78, 0x9A], ser.output.finalize());
+    }
+
+    #[test]
+    fn endian() {
+        use crate::encoding::{Be, Le, WordSwapped};
+        let buf = &mut [0xCDu8; 20];
+        let mut ser = Serializer::new(Slice::new(buf));
+        (
+            Le(0x1234u16),
+            WordSwapped(0x1122_3344u32),
+            WordSwapped(0x1122_3344_5566_7788u64),
+            0x1234u16,
+        )
+            .serialize(&mut ser)
+            .unwrap();
+        ser.endian = Endian::Little;
+        (0x1234u16, Be(0x1234u16)).serialize(&mut ser).unwrap();
+        assert_eq!(
+            &[
+                0x34, 0x12, 0x33, 0x44, 0x11, 0x22, 0x77, 0x88, 0x55, 0x66, 0x33, 0x44, 0x11, 0x22,
+                0x12, 0x34, 0x34, 0x12, 0x12, 0x34
+            ],
+            ser.output.finalize()
+        );
+    }
+
+    #[test]
+    fn bool_encoding() {
+        let buf = &mut [0u8; 6];
+        let mut ser = Serializer::new(Slice::new(buf));
+        ser.bools = BoolEncoding::Byte;
+        (true, false).serialize(&mut ser).unwrap();
+        ser.bools = BoolEncoding::Custom {
+            on: 0x5A5A,
+            off: 0xA5A5,
+        };
+        (true, false).serialize(&mut ser).unwrap();
+        assert_eq!(&[0x01, 0x00, 0x5A, 0x5A, 0xA5, 0xA5], ser.output.finalize());
+    }
+
+    #[test]
+    fn str() {
+        let buf = &mut [0u8; 8];
+        let mut ser = Serializer::new(Slice::new(buf));
+        (0x1234u16, "ABC").serialize(&mut ser).unwrap();
+        assert_eq!(
+            &[0x12, 0x34, b'A', b'B', b'C', 0xFF, 0xFF],
+            ser.output.finalize()
+        );
+    }
+
+    #[test]
+    fn collect_str() {
+        let buf = &mut [0u8; 8];
+        let mut ser = Serializer::new(Slice::new(buf));
+        ser::Serializer::collect_str(&mut ser, &format_args!("T={}C", 25)).unwrap();
+        assert_eq!(
+            &[b'T', b'=', b'2', b'5', b'C', 0xFF, 0xFF],
+            ser.output.finalize()
+        );
+    }
 }
@@ -0,0 +1,304 @@
+use super::{serializer::Serializer, Request, Storage, MAX_LEN};
+use crate::{
+    command::{Word, Write},
+    Error, Result,
+};
+use core::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+use serde::Serialize;
+
+/// Source of the requests of [`Frames`]
+pub trait FrameSource {
+    /// Returns the next request built on `output`, or [`None`] once all the requests are built.
+    fn next_request<S: Storage>(&mut self, output: S) -> Option<Result<Request<Write, S>>>;
+
+    /// Returns the bounds on the number of remaining requests, as [`Iterator::size_hint`].
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+/// Iterator over finalized frames
+///
+/// Each frame is built with a default initialized storage `S`, e.g. [`HVec`][super::HVec],
+/// and finalized with the optional CRC.
+/// The iteration stops after the first error.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Frames<R, S> {
+    source: R,
+    crc: bool,
+    done: bool,
+    output: PhantomData<S>,
+}
+
+impl<R: FrameSource, S: Storage + Default> Frames<R, S> {
+    /// Returns a new iterator over the frames of the requests of `source`.
+    pub fn from_source(source: R, crc: bool) -> Self {
+        Self {
+            source,
+            crc,
+            done: false,
+            output: PhantomData,
+        }
+    }
+}
+
+impl<R: FrameSource, S: Storage + Default> Iterator for Frames<R, S> {
+    type Item = Result<S::Output>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let frame = self
+            .source
+            .next_request(S::default())?
+            .and_then(|request| request.finalize(self.crc));
+        self.done = frame.is_err();
+        Some(frame)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            return (0, Some(0));
+        }
+        // Any error ends the iteration early
+        (0, self.source.size_hint().1)
+    }
+}
+
+/// Iterator over the finalized frames of a [`Word<Write>`] split with advancing addresses, see [`WordSplit`]
+pub type WordFrames<'v, T, S> = Frames<WordSplit<'v, T>, S>;
+
+impl<'v, T: Serialize + ?Sized, S: Storage + Default> WordFrames<'v, T, S> {
+    /// Returns a new iterator splitting `value` written to `addr` into frames of at most `max_frame_len` bytes.
+    ///
+    /// The frames are also kept within the [`MAX_LEN`] protocol limit.
+    /// A value serializing to nothing yields no frames.
+    /// Iterating fails with [`Error::RequestBadParam`] if `max_frame_len` can't fit a word.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "heapless")] {
+    /// use dguscard::request::{HVec, WordFrames};
+    ///
+    /// let samples = [[0u16; 20]; 10];
+    /// let frames = WordFrames::<_, HVec<300>>::new(&samples, 0x1000, 300, true);
+    /// let frames: Vec<_> = frames.map(|frame| frame.unwrap()).collect();
+    /// assert_eq!(frames.len(), 2);
+    /// // The first frame carries 250 bytes of payload, the most that fits the length field
+    /// assert_eq!(&frames[0][..6], &[0x5A, 0xA5, 255, 0x82, 0x10, 0x00]);
+    /// assert_eq!(&frames[1][..6], &[0x5A, 0xA5, 155, 0x82, 0x10, 0x7D]);
+    /// # }
+    /// ```
+    pub fn new(value: &'v T, addr: u16, max_frame_len: usize, crc: bool) -> Self {
+        let crc_len = if crc { 2 } else { 0 };
+        // Header, length, command code, address and CRC
+        let chunk = max_frame_len.saturating_sub(6 + crc_len);
+        // Command code, address and CRC after the length field
+        let chunk = chunk.min(MAX_LEN as usize - 3 - crc_len);
+        let split = WordSplit {
+            value,
+            addr,
+            chunk: chunk & !1,
+            offset: 0,
+            done: false,
+        };
+        Self::from_source(split, crc)
+    }
+}
+
+/// Requests of a value split into [`Word<Write>`] requests with advancing addresses
+///
+/// Each request carries a word aligned window of the serialized value,
+/// written to the address of the window's first word.
+/// The value is serialized again for every request, stopping at the end of the request's window,
+/// so splitting into `k` requests costs about `k / 2` serializations of the whole value.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct WordSplit<'v, T: ?Sized> {
+    value: &'v T,
+    addr: u16,
+    chunk: usize,
+    offset: usize,
+    done: bool,
+}
+
+impl<T: Serialize + ?Sized> WordSplit<'_, T> {
+    /// Returns the next request, or `None` if the value serializes to nothing.
+    fn request<S: Storage>(&mut self, output: S) -> Result<Option<Request<Write, S>>> {
+        if self.chunk == 0 {
+            return Err(Error::RequestBadParam);
+        }
+        let addr = u16::try_from(self.offset / 2)
+            .ok()
+            .and_then(|offset| self.addr.checked_add(offset))
+            .ok_or(Error::RequestBadParam)?;
+        let mut request = Request::new(output, Word { addr, cmd: Write })?;
+        let mut serializer = Serializer::new(Window {
+            storage: &mut request.serializer.output,
+            skip: self.offset,
+            take: self.chunk,
+            pos: 0,
+            full: false,
+        });
+        // A full window stops the serialization, with more bytes to follow
+        let more = match self.value.serialize(&mut serializer) {
+            Err(_) if serializer.output.full => true,
+            result => result.map(|_| false)?,
+        };
+        if !more && self.offset >= serializer.output.pos {
+            self.done = true;
+            return Ok(None);
+        }
+        self.offset += self.chunk;
+        self.done = !more;
+        Ok(Some(request))
+    }
+}
+
+impl<T: Serialize + ?Sized> FrameSource for WordSplit<'_, T> {
+    fn next_request<S: Storage>(&mut self, output: S) -> Option<Result<Request<Write, S>>> {
+        if self.done {
+            return None;
+        }
+        self.request(output).transpose()
+    }
+}
+
+/// A storage that only stores the bytes within a window of the serialization, discarding the rest.
+///
+/// Fails on the first byte past the window, setting `full`.
+struct Window<'a, S> {
+    storage: &'a mut S,
+    skip: usize,
+    take: usize,
+    pos: usize,
+    full: bool,
+}
+
+impl<S: Storage> Storage for Window<'_, S> {
+    type Output = ();
+
+    #[inline]
+    fn try_push(&mut self, data: u8) -> Result<()> {
+        if self.pos >= self.skip + self.take {
+            self.full = true;
+            return Err(Error::RequestBufferFull);
+        }
+        if self.pos >= self.skip {
+            self.storage.try_push(data)?;
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn finalize(self) -> Self::Output {}
+}
+
+impl<S: Storage> Deref for Window<'_, S> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.storage
+    }
+}
+
+impl<S: Storage> DerefMut for Window<'_, S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.storage
+    }
+}
+
+#[cfg(feature = "heapless")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::HVec;
+
+    #[test]
+    fn split() {
+        let value = [0x1000u16, 0x1001, 0x1002, 0x1003, 0x1004];
+        // 2 words per frame
+        let mut frames = WordFrames::<_, HVec<16>>::new(&value, 0x1000, 11, false);
+        assert_eq!(
+            &frames.next().unwrap().unwrap(),
+            &[0x5A, 0xA5, 7, 0x82, 0x10, 0x00, 0x10, 0x00, 0x10, 0x01]
+        );
+        assert_eq!(
+            &frames.next().unwrap().unwrap(),
+            &[0x5A, 0xA5, 7, 0x82, 0x10, 0x02, 0x10, 0x02, 0x10, 0x03]
+        );
+        assert_eq!(
+            &frames.next().unwrap().unwrap(),
+            &[0x5A, 0xA5, 5, 0x82, 0x10, 0x04, 0x10, 0x04]
+        );
+        assert!(frames.next().is_none());
+    }
+
+    #[test]
+    fn split_exact() {
+        let value = [0x1000u16, 0x1001];
+        let mut frames = WordFrames::<_, HVec<16>>::new(&value, 0x1000, 12, true);
+        assert_eq!(frames.next().unwrap().unwrap().len(), 12);
+        assert!(frames.next().is_none());
+    }
+
+    #[test]
+    fn split_stops_at_window() {
+        use core::cell::Cell;
+        use serde::ser::{SerializeTuple, Serializer};
+
+        // Counts the words serialized
+        struct Counted<'a>(&'a Cell<usize>);
+
+        impl Serialize for Counted<'_> {
+            fn serialize<S: Serializer>(
+                &self,
+                serializer: S,
+            ) -> core::result::Result<S::Ok, S::Error> {
+                let mut tuple = serializer.serialize_tuple(10)?;
+                for word in 0..10u16 {
+                    tuple.serialize_element(&word)?;
+                    self.0.set(self.0.get() + 1);
+                }
+                tuple.end()
+            }
+        }
+
+        let count = Cell::new(0);
+        let value = Counted(&count);
+        // 2 words per frame
+        let frames = WordFrames::<_, HVec<16>>::new(&value, 0x1000, 10, false);
+        let frames: heapless::Vec<_, 5> = frames.map(|frame| frame.unwrap()).collect();
+        assert_eq!(frames.len(), 5);
+        assert_eq!(
+            &frames[4],
+            &[0x5A, 0xA5, 7, 0x82, 0x10, 0x08, 0x00, 0x08, 0x00, 0x09]
+        );
+        // 2 + 4 + 6 + 8 + 10 words instead of 5 * 10
+        assert_eq!(count.get(), 30);
+    }
+
+    #[test]
+    fn split_empty() {
+        let mut frames = WordFrames::<_, HVec<16>>::new(&(), 0x1000, 16, true);
+        assert!(frames.next().is_none());
+        let value: [u16; 0] = [];
+        let mut frames = WordFrames::<_, HVec<16>>::new(&value, 0x1000, 16, false);
+        assert!(frames.next().is_none());
+        assert!(frames.next().is_none());
+    }
+
+    #[test]
+    fn split_too_small() {
+        let value = [0x1000u16, 0x1001];
+        let mut frames = WordFrames::<_, HVec<16>>::new(&value, 0x1000, 9, true);
+        assert_eq!(Some(Err(Error::RequestBadParam)), frames.next());
+        assert!(frames.next().is_none());
+    }
+}
@@ -28,10 +28,38 @@ pub trait Storage: Deref<Target = [u8]> + DerefMut<Target = [u8]> {
     /// Can be used to push a single byte to be modified and/or stored.
     fn try_push(&mut self, data: u8) -> Result<()>;
 
+    /// Returns the number of bytes that can still be stored.
+    ///
+    /// Unbounded by default, storages with a fixed capacity should override it.
+    #[inline]
+    fn remaining_capacity(&self) -> usize {
+        usize::MAX
+    }
+
+    /// Returns the current position to be restored later with [`Storage::rollback`].
+    #[inline]
+    fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.len())
+    }
+
+    /// Discards the bytes stored after the given checkpoint.
+    ///
+    /// Fails with [`Error::RequestBadParam`] if the checkpoint is past the stored bytes.
+    /// Fails with [`Error::NotYetImplemented`] by default, storages supporting it should override it.
+    #[inline]
+    fn rollback(&mut self, _checkpoint: Checkpoint) -> Result<()> {
+        Err(Error::NotYetImplemented)
+    }
+
     /// Finalize the serialization process.
     fn finalize(self) -> Self::Output;
 }
 
+/// Position of a [`Storage`], returned by [`Storage::checkpoint`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Checkpoint(pub usize);
+
 /// A storage type that uses plain [`u8`] slice
 ///
 /// Stores the serialized bytes into a plain [`u8`] slice.
@@ -62,6 +90,18 @@ impl<'a> Storage for Slice<'a> {
         Ok(())
     }
 
+    fn remaining_capacity(&self) -> usize {
+        self.buf.len() - self.index
+    }
+
+    fn rollback(&mut self, checkpoint: Checkpoint) -> Result<()> {
+        if checkpoint.0 > self.index {
+            return Err(Error::RequestBadParam);
+        }
+        self.index = checkpoint.0;
+        Ok(())
+    }
+
     fn finalize(self) -> Self::Output {
         &mut self.buf[..self.index]
     }
@@ -115,6 +155,18 @@ mod hvec {
             self.0.push(data).map_err(|_| Error::RequestBufferFull)
         }
 
+        fn remaining_capacity(&self) -> usize {
+            N - self.0.len()
+        }
+
+        fn rollback(&mut self, checkpoint: Checkpoint) -> Result<()> {
+            if checkpoint.0 > self.0.len() {
+                return Err(Error::RequestBadParam);
+            }
+            self.0.truncate(checkpoint.0);
+            Ok(())
+        }
+
         fn finalize(self) -> Self::Output {
             self.0
         }
@@ -1,34 +1,88 @@
-use crate::{Error, Result};
+use crate::{
+    encoding::{BoolEncoding, Endian, BE, LE, PAD, WORD_SWAPPED},
+    Error, Result,
+};
 use serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
 
 /// `serde` compatible deserializer.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Deserializer<'de> {
     pub input: &'de [u8],
+    /// Length of the input at the start of the first word
+    pub base: usize,
+    /// Byte order of the numbers
+    pub endian: Endian,
+    /// Encoding of the `bool` values
+    pub bools: BoolEncoding,
+    /// Set once a byte array took the rest of the input, failing any field after it
+    pub rest_taken: bool,
 }
 
-// Generic trait for blanket impl of big endian deserialization
-trait DeserializeBigEndian<T> {
-    fn deserialize_be(&mut self) -> Result<T>;
+impl<'de> Deserializer<'de> {
+    /// Returns a new deserializer with the first word starting at the beginning of the input.
+    pub fn new(input: &'de [u8]) -> Self {
+        Self {
+            input,
+            base: input.len(),
+            endian: Endian::Big,
+            bools: BoolEncoding::Word,
+            rest_taken: false,
+        }
+    }
+
+    /// Returns true if the remaining input starts on a word boundary.
+    pub fn is_word_aligned(&self) -> bool {
+        (self.base - self.input.len()) % 2 == 0
+    }
+
+    /// Skips a byte if the remaining input starts on a half word.
+    pub fn align_to_word(&mut self) -> Result<()> {
+        if !self.is_word_aligned() {
+            let (_, rest) = self
+                .input
+                .split_first()
+                .ok_or(Error::DeserializeUnexpectedEnd)?;
+            self.input = rest;
+        }
+        Ok(())
+    }
+
+    /// Deserializes a value with the given byte order, restoring the current one afterwards.
+    fn with_endian<V>(&mut self, endian: Endian, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let prev = core::mem::replace(&mut self.endian, endian);
+        let result = visitor.visit_newtype_struct(&mut *self);
+        self.endian = prev;
+        result
+    }
 }
 
-// Big endian deserialization macro
-macro_rules! impl_deserialize_be{
+// Generic trait for blanket impl of number deserialization
+trait DeserializeNumber<T> {
+    fn deserialize_number(&mut self) -> Result<T>;
+}
+
+// Number deserialization macro, honoring the current byte order
+macro_rules! impl_deserialize_number{
     ($($ty:ident)+) => ($(
-        impl DeserializeBigEndian<$ty> for Deserializer<'_> {
+        impl DeserializeNumber<$ty> for Deserializer<'_> {
             #[inline]
-            fn deserialize_be(&mut self) -> Result<$ty> {
+            fn deserialize_number(&mut self) -> Result<$ty> {
                 let (bytes, rest) = self.input.split_first_chunk().ok_or(Error::DeserializeUnexpectedEnd)?;
                 self.input = rest;
-                Ok($ty::from_be_bytes(*bytes))
+                let mut bytes = *bytes;
+                self.endian.reorder(&mut bytes);
+                Ok($ty::from_be_bytes(bytes))
             }
         }
     )+)
 }
 
 // Deserialize following types with the macro
-impl_deserialize_be! { u8 u16 u32 u64 u128 i8 i16 i32 i64 i128 f32 f64 }
+impl_deserialize_number! { u8 u16 u32 u64 u128 i8 i16 i32 i64 i128 f32 f64 }
 
 /// Serde deserializer implementation
 impl<'de> de::Deserializer<'de> for &'_ mut Deserializer<'de> {
@@ -52,11 +106,24 @@ impl<'de> de::Deserializer<'de> for &'_ mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        // Take a boolean encoded as u16
-        let v: u16 = self.deserialize_be()?;
+        let v: u16 = match self.bools {
+            BoolEncoding::Byte | BoolEncoding::LenientByte => {
+                DeserializeNumber::<u8>::deserialize_number(self)?.into()
+            }
+            _ => self.deserialize_number()?,
+        };
+        let (on, off) = match self.bools {
+            BoolEncoding::Custom { on, off } => (on, off),
+            _ => (1, 0),
+        };
+        let lenient = matches!(
+            self.bools,
+            BoolEncoding::LenientWord | BoolEncoding::LenientByte
+        );
         let v = match v {
-            0 => false,
-            1 => true,
+            v if v == on => true,
+            v if v == off => false,
+            _ if lenient => true,
             _ => return Err(Error::DeserializeBadBool),
         };
         visitor.visit_bool(v)
@@ -67,7 +134,7 @@ impl<'de> de::Deserializer<'de> for &'_ mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_i8(self.deserialize_be()?)
+        visitor.visit_i8(self.deserialize_number()?)
     }
 
     #[inline]
@@ -75,7 +142,7 @@ impl<'de> de::Deserializer<'de> for &'_ mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_i16(self.deserialize_be()?)
+        visitor.visit_i16(self.deserialize_number()?)
     }
 
     #[inline]
@@ -83,7 +150,7 @@ impl<'de> de::Deserializer<'de> for &'_ mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_i32(self.deserialize_be()?)
+        visitor.visit_i32(self.deserialize_number()?)
     }
 
     #[inline]
@@ -91,7 +158,7 @@ impl<'de> de::Deserializer<'de> for &'_ mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_i64(self.deserialize_be()?)
+        visitor.visit_i64(self.deserialize_number()?)
     }
 
     #[inline]
@@ -99,7 +166,7 @@ impl<'de> de::Deserializer<'de> for &'_ mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_i128(self.deserialize_be()?)
+        visitor.visit_i128(self.deserialize_number()?)
     }
 
     #[inline]
@@ -107,7 +174,7 @@ impl<'de> de::Deserializer<'de> for &'_ mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_u8(self.deserialize_be()?)
+        visitor.visit_u8(self.deserialize_number()?)
     }
 
     #[inline]
@@ -115,7 +182,7 @@ impl<'de> de::Deserializer<'de> for &'_ mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_u16(self.deserialize_be()?)
+        visitor.visit_u16(self.deserialize_number()?)
     }
 
     #[inline]
@@ -123,7 +190,7 @@ impl<'de> de::Deserializer<'de> for &'_ mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_u32(self.deserialize_be()?)
+        visitor.visit_u32(self.deserialize_number()?)
     }
 
     #[inline]
@@ -131,7 +198,7 @@ impl<'de> de::Deserializer<'de> for &'_ mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_u64(self.deserialize_be()?)
+        visitor.visit_u64(self.deserialize_number()?)
     }
 
     #[inline]
@@ -139,7 +206,7 @@ impl<'de> de::Deserializer<'de> for &'_ mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_u128(self.deserialize_be()?)
+        visitor.visit_u128(self.deserialize_number()?)
     }
 
     #[inline]
@@ -147,7 +214,7 @@ impl<'de> de::Deserializer<'de> for &'_ mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_f32(self.deserialize_be()?)
+        visitor.visit_f32(self.deserialize_number()?)
     }
 
     #[inline]
@@ -155,7 +222,7 @@ impl<'de> de::Deserializer<'de> for &'_ mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_f64(self.deserialize_be()?)
+        visitor.visit_f64(self.deserialize_number()?)
     }
 
     #[inline]
@@ -167,35 +234,45 @@ impl<'de> de::Deserializer<'de> for &'_ mut Deserializer<'de> {
     }
 
     #[inline]
-    fn deserialize_str<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        Err(Error::NotYetImplemented)
+        // Take a text up to the 0xFFFF terminator or the end of the input
+        let (text, rest) = match self.input.windows(2).position(|w| w == [0xFF, 0xFF]) {
+            Some(len) => (&self.input[..len], &self.input[len + 2..]),
+            None => (self.input, &self.input[self.input.len()..]),
+        };
+        let text = core::str::from_utf8(text).map_err(|_| Error::DeserializeBadUtf8)?;
+        self.input = rest;
+        visitor.visit_borrowed_str(text)
     }
 
     #[inline]
-    fn deserialize_string<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        Err(Error::NotYetImplemented)
+        self.deserialize_str(visitor)
     }
 
     #[inline]
-    fn deserialize_bytes<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        Err(Error::NotYetImplemented)
+        // Take the rest of the input, so it must be the last field
+        let bytes = core::mem::take(&mut self.input);
+        self.rest_taken = true;
+        visitor.visit_borrowed_bytes(bytes)
     }
 
     #[inline]
-    fn deserialize_byte_buf<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        Err(Error::NotYetImplemented)
+        self.deserialize_bytes(visitor)
     }
 
     #[inline]
@@ -215,19 +292,27 @@ impl<'de> de::Deserializer<'de> for &'_ mut Deserializer<'de> {
     }
 
     #[inline]
-    fn deserialize_unit_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
+        if name == PAD {
+            self.align_to_word()?;
+        }
         self.deserialize_unit(visitor)
     }
 
     #[inline]
-    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_newtype_struct(self)
+        match name {
+            BE => self.with_endian(Endian::Big, visitor),
+            LE => self.with_endian(Endian::Little, visitor),
+            WORD_SWAPPED => self.with_endian(Endian::WordSwapped, visitor),
+            _ => visitor.visit_newtype_struct(self),
+        }
     }
 
     #[inline]
@@ -347,7 +432,7 @@ impl<'de> serde::de::EnumAccess<'de> for &'_ mut Deserializer<'de> {
 
     #[inline]
     fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self)> {
-        let v: u16 = self.deserialize_be()?;
+        let v: u16 = self.deserialize_number()?;
         let v = DeserializeSeed::deserialize(seed, v.into_deserializer())?;
         Ok((v, self))
     }
@@ -364,6 +449,10 @@ impl<'a, 'b: 'a> serde::de::SeqAccess<'b> for SeqAccess<'a, 'b> {
     #[inline]
     fn next_element_seed<V: DeserializeSeed<'b>>(&mut self, seed: V) -> Result<Option<V::Value>> {
         if self.len > 0 {
+            if self.deserializer.rest_taken && self.deserializer.input.is_empty() {
+                // A byte array before this field took its input
+                return Err(Error::DeserializeUnexpectedEnd);
+            }
             self.len -= 1;
             Ok(Some(DeserializeSeed::deserialize(
                 seed,
@@ -383,21 +472,21 @@ mod tests {
     #[test]
     fn u8_single() {
         let input = &[0xDB];
-        let mut de = Deserializer { input };
+        let mut de = Deserializer::new(input);
         assert_eq!(0xDB, u8::deserialize(&mut de).unwrap());
     }
 
     #[test]
     fn u16_single() {
         let input = &[0xDE, 0xBE];
-        let mut de = Deserializer { input };
+        let mut de = Deserializer::new(input);
         assert_eq!(0xDEBE, u16::deserialize(&mut de).unwrap());
     }
 
     #[test]
     fn u32_single() {
         let input = &[0xDE, 0xAD, 0xBE, 0xEF];
-        let mut de = Deserializer { input };
+        let mut de = Deserializer::new(input);
         assert_eq!(0xDEADBEEF, u32::deserialize(&mut de).unwrap());
         assert!(de.input.is_empty());
     }
@@ -405,7 +494,7 @@ mod tests {
     #[test]
     fn u64_single() {
         let input = &[0xDE, 0xAD, 0xBE, 0xEF, 0xFE, 0xEB, 0xDA, 0xED];
-        let mut de = Deserializer { input };
+        let mut de = Deserializer::new(input);
         assert_eq!(0xDEADBEEFFEEBDAED, u64::deserialize(&mut de).unwrap());
         assert!(de.input.is_empty());
     }
@@ -416,7 +505,7 @@ mod tests {
             0xDE, 0xAD, 0xBE, 0xEF, 0xFE, 0xEB, 0xDA, 0xED, 0x12, 0x23, 0x34, 0x45, 0x56, 0x67,
             0x78, 0x89,
         ];
-        let mut de = Deserializer { input };
+        let mut de = Deserializer::new(input);
         assert_eq!(
             0xDEADBEEFFEEBDAED1223344556677889,
             u128::deserialize(&mut de).unwrap()
@@ -431,7 +520,7 @@ mod tests {
             0x78, 0x89, 0x10, 0x44, 0x33, 0x22, 0x11, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22,
             0x11, 0xDE, 0xAD,
         ];
-        let mut de = Deserializer { input };
+        let mut de = Deserializer::new(input);
         type TestTuple = (u128, u8, u32, u64, u16);
         assert_eq!(
             (
@@ -449,7 +538,7 @@ mod tests {
     #[test]
     fn u8_array() {
         let input = &[0xDE, 0xAD, 0xBE, 0xEF];
-        let mut de = Deserializer { input };
+        let mut de = Deserializer::new(input);
         type TestArray = [u8; 4];
         assert_eq!(
             [0xDE, 0xAD, 0xBE, 0xEF],
@@ -463,7 +552,7 @@ mod tests {
         let input = &[
             0xDE, 0xAD, 0xBE, 0xEF, 0x12, 0x34, 0x56, 0x78, 0xFE, 0x12, 0xCD, 0x34,
         ];
-        let mut de = Deserializer { input };
+        let mut de = Deserializer::new(input);
         type TestArray = [u16; 6];
         assert_eq!(
             [0xDEAD, 0xBEEF, 0x1234, 0x5678, 0xFE12, 0xCD34],
@@ -477,7 +566,7 @@ mod tests {
         let input = &[
             0xDE, 0xAD, 0xBE, 0xEF, 0x12, 0x34, 0x56, 0x78, 0xFE, 0x12, 0xCD, 0x34,
         ];
-        let mut de = Deserializer { input };
+        let mut de = Deserializer::new(input);
         type TestArray = [u32; 3];
         assert_eq!(
             [0xDEADBEEF, 0x12345678, 0xFE12CD34],
@@ -489,7 +578,7 @@ mod tests {
     #[test]
     fn bool_true() {
         let input = &[0x00, 0x01];
-        let mut de = Deserializer { input };
+        let mut de = Deserializer::new(input);
         assert!(bool::deserialize(&mut de).unwrap());
         assert!(de.input.is_empty());
     }
@@ -497,7 +586,7 @@ mod tests {
     #[test]
     fn bool_false() {
         let input = &[0x00, 0x00];
-        let mut de = Deserializer { input };
+        let mut de = Deserializer::new(input);
         assert!(!bool::deserialize(&mut de).unwrap());
         assert!(de.input.is_empty());
     }
@@ -505,7 +594,7 @@ mod tests {
     #[test]
     fn bool_bad() {
         let input = &[0x01, 0x00];
-        let mut de = Deserializer { input };
+        let mut de = Deserializer::new(input);
         assert_eq!(Err(Error::DeserializeBadBool), bool::deserialize(&mut de));
         assert!(de.input.is_empty());
     }
@@ -520,8 +609,112 @@ mod tests {
         }
 
         let input = &[0x00, 0x02];
-        let mut de = Deserializer { input };
+        let mut de = Deserializer::new(input);
         assert_eq!(Ok(Test::Two), Test::deserialize(&mut de));
         assert!(de.input.is_empty());
     }
+
+    #[test]
+    fn pad() {
+        use crate::encoding::Pad;
+        let input = &[0x12, 0xFF, 0x34, 0x56, 0x78];
+        let mut de = Deserializer::new(input);
+        assert_eq!(
+            (0x12, Pad, 0x3456, Pad, 0x78),
+            <(u8, Pad, u16, Pad, u8)>::deserialize(&mut de).unwrap()
+        );
+        assert!(!de.is_word_aligned());
+        assert_eq!(
+            Err(Error::DeserializeUnexpectedEnd),
+            Pad::deserialize(&mut de)
+        );
+    }
+
+    #[test]
+    fn endian() {
+        use crate::encoding::{Be, Le, WordSwapped};
+        let input = &[
+            0x34, 0x12, 0x33, 0x44, 0x11, 0x22, 0x77, 0x88, 0x55, 0x66, 0x33, 0x44, 0x11, 0x22,
+            0x12, 0x34, 0x34, 0x12, 0x12, 0x34,
+        ];
+        let mut de = Deserializer::new(input);
+        assert_eq!(
+            (
+                Le(0x1234u16),
+                WordSwapped(0x1122_3344u32),
+                WordSwapped(0x1122_3344_5566_7788u64),
+                0x1234u16,
+            ),
+            <(Le<u16>, WordSwapped<u32>, WordSwapped<u64>, u16)>::deserialize(&mut de).unwrap()
+        );
+        de.endian = Endian::Little;
+        assert_eq!(
+            (0x1234u16, Be(0x1234u16)),
+            <(u16, Be<u16>)>::deserialize(&mut de).unwrap()
+        );
+        assert!(de.input.is_empty());
+    }
+
+    #[test]
+    fn bool_encoding() {
+        let input = &[0x01, 0x07, 0x00, 0x05, 0x5A, 0x5A, 0xA5, 0xA5, 0x12, 0x34];
+        let mut de = Deserializer::new(input);
+        de.bools = BoolEncoding::Byte;
+        assert_eq!(Ok(true), bool::deserialize(&mut de));
+        assert_eq!(Err(Error::DeserializeBadBool), bool::deserialize(&mut de));
+        de.bools = BoolEncoding::LenientWord;
+        assert_eq!(Ok(true), bool::deserialize(&mut de));
+        de.bools = BoolEncoding::Custom {
+            on: 0x5A5A,
+            off: 0xA5A5,
+        };
+        assert_eq!(Ok((true, false)), <(bool, bool)>::deserialize(&mut de));
+        assert_eq!(Err(Error::DeserializeBadBool), bool::deserialize(&mut de));
+    }
+
+    #[test]
+    fn borrowed() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Msg<'a> {
+            id: u16,
+            name: &'a str,
+            raw: &'a [u8],
+        }
+
+        let input = &[0x12, 0x34, b'A', b'B', b'C', 0xFF, 0xFF, 0x01, 0xFF, 0xFF];
+        let mut de = Deserializer::new(input);
+        assert_eq!(
+            Msg {
+                id: 0x1234,
+                name: "ABC",
+                raw: &[0x01, 0xFF, 0xFF]
+            },
+            Msg::deserialize(&mut de).unwrap()
+        );
+        assert!(de.input.is_empty());
+
+        // Only the last field can be a byte array
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct NotLast<'a> {
+            raw: &'a [u8],
+            unit: (),
+        }
+        let mut de = Deserializer::new(input);
+        assert_eq!(
+            Err(Error::DeserializeUnexpectedEnd),
+            NotLast::deserialize(&mut de).map(|_| ())
+        );
+        let mut de = Deserializer::new(input);
+        assert_eq!(
+            Err(Error::DeserializeUnexpectedEnd),
+            <((u16, &[u8]), &str)>::deserialize(&mut de).map(|_| ())
+        );
+
+        let input = &[b'A', 0xC0];
+        let mut de = Deserializer::new(input);
+        assert_eq!(Err(Error::DeserializeBadUtf8), <&str>::deserialize(&mut de));
+        let mut de = Deserializer::new(&input[..1]);
+        assert_eq!(Ok("A"), <&str>::deserialize(&mut de));
+    }
 }
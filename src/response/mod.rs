@@ -8,24 +8,158 @@ pub use self::accumulator::{Accumulator, FeedResult};
 use self::deserializer::Deserializer;
 use crate::{
     command::{Command, Curve, Dword, Read, Register, Word, Write},
+    encoding::{BoolEncoding, Endian},
     Error::*,
     Result, CRC, HEADER,
 };
 use serde::Deserialize;
 
 /// [`serde`] compatible deserializer wrapping over raw data part of the response
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Content<'de> {
     deserializer: Deserializer<'de>,
+    /// Whole data part of the response
+    bytes: &'de [u8],
+    /// Address of the first byte
+    addr: u32,
+    /// Number of bytes per address
+    unit: usize,
 }
 
 impl<'de> Content<'de> {
+    /// Returns a new content with the first word starting at the remaining input of the deserializer.
+    fn new(mut deserializer: Deserializer<'de>, addr: u32, unit: usize) -> Self {
+        deserializer.base = deserializer.input.len();
+        Self {
+            bytes: deserializer.input,
+            deserializer,
+            addr,
+            unit,
+        }
+    }
+
     /// Removes a `T` from content and returns it.
     pub fn take<T: Deserialize<'de>>(&mut self) -> Result<T> {
         T::deserialize(&mut self.deserializer)
     }
 
+    /// Returns the next `T` from content without removing it.
+    pub fn peek<T: Deserialize<'de>>(&self) -> Result<T> {
+        T::deserialize(&mut self.deserializer.clone())
+    }
+
+    /// Removes the next `n` words from content.
+    pub fn skip_words(&mut self, n: usize) -> Result<()> {
+        let len = n.checked_mul(2).ok_or(DeserializeUnexpectedEnd)?;
+        let (_, rest) = self
+            .deserializer
+            .input
+            .split_at_checked(len)
+            .ok_or(DeserializeUnexpectedEnd)?;
+        self.deserializer.input = rest;
+        Ok(())
+    }
+
+    /// Moves to the given address, forwards or backwards.
+    ///
+    /// The address is in the units of the response command, i.e. bytes for [`Register`],
+    /// words for [`Word`] and double words for [`Dword`].
+    /// Fails with [`Error::ResponseBadAddr`][crate::Error::ResponseBadAddr] if the address is outside of the content.
+    /// The position is left unchanged if it fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use dguscard::response::Response;
+    ///
+    /// let rx = &[0x5A, 0xA5, 10, 0x83, 0x10, 0x00, 3, 0x00, 0x01, 0x00, 0x02, 0x00, 0x03];
+    /// let Response::WordData { mut content, .. } = Response::from_bytes(rx, false).unwrap() else {
+    ///     panic!("Unexpected response");
+    /// };
+    /// content.seek_to_addr(0x1002).unwrap();
+    /// assert_eq!(content.take::<u16>().unwrap(), 3);
+    /// content.seek_to_addr(0x1000).unwrap();
+    /// assert_eq!(content.take::<u16>().unwrap(), 1);
+    /// ```
+    pub fn seek_to_addr(&mut self, addr: u32) -> Result<()> {
+        let offset = addr
+            .checked_sub(self.addr)
+            .and_then(|offset| (offset as usize).checked_mul(self.unit))
+            .ok_or(ResponseBadAddr)?;
+        self.deserializer.input = self.bytes.get(offset..).ok_or(ResponseBadAddr)?;
+        Ok(())
+    }
+
+    /// Returns the address of the current position.
+    ///
+    /// The address is in the units of the response command, see [`Content::seek_to_addr`].
+    pub fn current_addr(&self) -> u32 {
+        self.addr_at(self.offset())
+    }
+
+    /// Moves to the given address and removes a `T` from there.
+    ///
+    /// Fails with [`Error::ResponseBadAddr`][crate::Error::ResponseBadAddr] if the address is outside of the content.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use dguscard::response::Response;
+    ///
+    /// let rx = &[0x5A, 0xA5, 10, 0x83, 0x10, 0x00, 3, 0x00, 0x01, 0x00, 0x02, 0x00, 0x03];
+    /// let Response::WordData { mut content, .. } = Response::from_bytes(rx, false).unwrap() else {
+    ///     panic!("Unexpected response");
+    /// };
+    /// assert_eq!(content.take_at::<u16>(0x1001).unwrap(), 2);
+    /// assert_eq!(content.current_addr(), 0x1002);
+    /// assert!(content.take_at::<u16>(0x1003).is_err());
+    /// assert_eq!(content.current_addr(), 0x1002);
+    /// ```
+    pub fn take_at<T: Deserialize<'de>>(&mut self, addr: u32) -> Result<T> {
+        let input = self.deserializer.input;
+        self.seek_to_addr(addr)
+            .and_then(|_| {
+                if self.is_empty() {
+                    return Err(ResponseBadAddr);
+                }
+                self.take()
+            })
+            .inspect_err(|_| self.deserializer.input = input)
+    }
+
+    /// Returns the remaining bytes in the content without removing them.
+    pub fn remaining_bytes(&self) -> &'de [u8] {
+        self.deserializer.input
+    }
+
+    /// Removes the next `words` words from content and returns them as a new content.
+    ///
+    /// The new content starts at the current address and keeps the current encoding settings.
+    pub fn split_off(&mut self, words: usize) -> Result<Content<'de>> {
+        let len = words.checked_mul(2).ok_or(DeserializeUnexpectedEnd)?;
+        let (head, rest) = self
+            .deserializer
+            .input
+            .split_at_checked(len)
+            .ok_or(DeserializeUnexpectedEnd)?;
+        let mut deserializer = self.deserializer.clone();
+        deserializer.input = head;
+        let content = Content::new(deserializer, self.addr_at(self.offset()), self.unit);
+        self.deserializer.input = rest;
+        Ok(content)
+    }
+
+    /// Returns the number of bytes removed from the content.
+    fn offset(&self) -> usize {
+        self.bytes.len() - self.deserializer.input.len()
+    }
+
+    /// Returns the address of the given byte offset.
+    fn addr_at(&self, offset: usize) -> u32 {
+        self.addr.wrapping_add((offset / self.unit) as u32)
+    }
+
     /// Returns the number of remaining bytes in the content.
     pub fn len(&self) -> usize {
         self.deserializer.input.len()
@@ -35,6 +169,29 @@ impl<'de> Content<'de> {
     pub fn is_empty(&self) -> bool {
         self.deserializer.input.is_empty()
     }
+
+    /// Returns true if the remaining bytes start on a word boundary.
+    pub fn is_word_aligned(&self) -> bool {
+        self.deserializer.is_word_aligned()
+    }
+
+    /// Skips a byte if the remaining bytes start on a half word.
+    pub fn align_to_word(&mut self) -> Result<()> {
+        self.deserializer.align_to_word()
+    }
+
+    /// Sets the byte order of the numbers in the remaining content, big-endian by default.
+    ///
+    /// Single values can override it with the [`Be`][crate::encoding::Be], [`Le`][crate::encoding::Le]
+    /// and [`WordSwapped`][crate::encoding::WordSwapped] wrappers.
+    pub fn set_endian(&mut self, endian: Endian) {
+        self.deserializer.endian = endian;
+    }
+
+    /// Sets the encoding of the `bool` values in the remaining content, [`BoolEncoding::Word`] by default.
+    pub fn set_bool_encoding(&mut self, bools: BoolEncoding) {
+        self.deserializer.bools = bools;
+    }
 }
 
 /// Response parser
@@ -142,7 +299,7 @@ impl<'de> Response<'de> {
     /// i.e. excluding header, length, and CRC if enabled.
     /// Intended to be used with an Accumulator.
     pub fn from_content_bytes(input: &'de [u8]) -> Result<Self> {
-        let mut deserializer = Deserializer { input };
+        let mut deserializer = Deserializer::new(input);
         // Strip command code from input
         let opcode = u8::deserialize(&mut deserializer)?;
         use Response::*;
@@ -164,18 +321,21 @@ impl<'de> Response<'de> {
         // Or is it data?
         else {
             let response = match opcode {
-                Register::<Read>::CMD => RegisterData {
-                    cmd: Register::deserialize(&mut deserializer)?,
-                    content: Content { deserializer },
-                },
-                Word::<Read>::CMD => WordData {
-                    cmd: Word::deserialize(&mut deserializer)?,
-                    content: Content { deserializer },
-                },
-                Dword::<Read>::CMD => DwordData {
-                    cmd: Dword::deserialize(&mut deserializer)?,
-                    content: Content { deserializer },
-                },
+                Register::<Read>::CMD => {
+                    let cmd = Register::<Read>::deserialize(&mut deserializer)?;
+                    let content = Content::new(deserializer, cmd.addr.into(), 1);
+                    RegisterData { cmd, content }
+                }
+                Word::<Read>::CMD => {
+                    let cmd = Word::<Read>::deserialize(&mut deserializer)?;
+                    let content = Content::new(deserializer, cmd.addr.into(), 2);
+                    WordData { cmd, content }
+                }
+                Dword::<Read>::CMD => {
+                    let cmd = Dword::<Read>::deserialize(&mut deserializer)?;
+                    let content = Content::new(deserializer, cmd.addr, 4);
+                    DwordData { cmd, content }
+                }
                 _ => return Err(ResponseUnknownCmd),
             };
             Ok(response)
@@ -217,6 +377,15 @@ impl<'de> Response<'de> {
     }
 }
 
+/// Parses a [`Response::WordData`] frame without CRC and returns its command and content.
+#[cfg(test)]
+pub(crate) fn word_data(input: &[u8]) -> (Word<Read>, Content<'_>) {
+    let Response::WordData { cmd, content } = Response::from_bytes(input, false).unwrap() else {
+        panic!("Expected WordData response");
+    };
+    (cmd, content)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -230,4 +399,71 @@ mod tests {
         };
         assert_eq!(rest, &[1, 2, 3, 4]);
     }
+
+    #[test]
+    fn content_align() {
+        let input = [0x5A, 0xA5, 8, 0x83, 0x10, 0x00, 2, 0x01, 0x02, 0x03, 0x04];
+        let (_, mut content) = word_data(&input);
+        assert!(content.is_word_aligned());
+        assert_eq!(0x01, content.take::<u8>().unwrap());
+        assert!(!content.is_word_aligned());
+        content.align_to_word().unwrap();
+        assert_eq!(0x0304, content.take::<u16>().unwrap());
+    }
+
+    #[test]
+    fn content_navigate() {
+        let input = [
+            0x5A, 0xA5, 12, 0x83, 0x10, 0x00, 4, 0x00, 0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x04,
+        ];
+        let (_, mut content) = word_data(&input);
+        assert_eq!(Ok(0x0001u16), content.peek());
+        content.skip_words(1).unwrap();
+        assert_eq!(
+            &[0x00, 0x02, 0x00, 0x03, 0x00, 0x04],
+            content.remaining_bytes()
+        );
+        let mut sub = content.split_off(2).unwrap();
+        assert_eq!(Ok(0x0004u16), content.take());
+        assert!(content.is_empty());
+        assert_eq!(Ok(0x0002u16), sub.peek());
+        sub.seek_to_addr(0x1002).unwrap();
+        assert_eq!(Ok(0x0003u16), sub.take());
+        assert_eq!(Err(ResponseBadAddr), sub.seek_to_addr(0x1000));
+        assert_eq!(Err(ResponseBadAddr), sub.seek_to_addr(0x1004));
+        assert_eq!(Err(DeserializeUnexpectedEnd), sub.skip_words(2));
+        content.seek_to_addr(0x1000).unwrap();
+        assert_eq!(Ok(0x0001u16), content.take());
+    }
+
+    #[test]
+    fn content_addr() {
+        let input = [0x5A, 0xA5, 8, 0x81, 0x00, 0x10, 4, 0x01, 0x02, 0x03, 0x04];
+        let Response::RegisterData { mut content, .. } =
+            Response::from_bytes(&input, false).unwrap()
+        else {
+            panic!("Expected RegisterData response");
+        };
+        assert_eq!(0x10, content.current_addr());
+        assert_eq!(Ok(0x0304u16), content.take_at(0x12));
+        assert_eq!(0x14, content.current_addr());
+        assert_eq!(Ok(0x02u8), content.take_at(0x11));
+        assert_eq!(Err(ResponseBadAddr), content.take_at::<u8>(0x0F));
+        assert_eq!(Err(ResponseBadAddr), content.take_at::<u8>(0x14));
+        assert_eq!(Err(DeserializeUnexpectedEnd), content.take_at::<u32>(0x11));
+        assert_eq!(Err(DeserializeBadBool), content.take_at::<bool>(0x10));
+        // Failures leave the position unchanged
+        assert_eq!(0x12, content.current_addr());
+        assert_eq!(Ok(0x03u8), content.take());
+
+        let input = [
+            0x5A, 0xA5, 14, 0x87, 0x00, 0x00, 0x20, 0x00, 2, 0, 0, 0, 1, 0, 0, 0, 2,
+        ];
+        let Response::DwordData { mut content, .. } = Response::from_bytes(&input, false).unwrap()
+        else {
+            panic!("Expected DwordData response");
+        };
+        assert_eq!(Ok(2u32), content.take_at(0x2001));
+        assert_eq!(Err(ResponseBadAddr), content.take_at::<u32>(0x2002));
+    }
 }
@@ -0,0 +1,644 @@
+//! Description pointer (SP) helpers
+//!
+//! Every display control has a description pointer (SP) block holding its properties, e.g. VP, position, and color.
+//! Writing into the SP block changes the properties of the control at runtime.
+//! The descriptors cover the leading properties of the SP blocks, as laid out in the DGUS II documentation.
+
+use crate::{
+    color::Rgb565,
+    command::{Word, Write},
+    encoding::Pad,
+    graphics::Point,
+    request::{Request, Storage},
+    Error, Result,
+};
+use core::marker::PhantomData;
+use serde::{Deserialize, Serialize};
+
+/// Descriptor property
+///
+/// Typed word offset of a property within an SP block.
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::{request::Slice, sp::Icon};
+///
+/// // Limit the icon control at SP 0x6000 to the icons 10..=20
+/// let buf = &mut [0u8; 16];
+/// let frame = Icon::ICON_MIN
+///     .request(0x6000, &10, Slice::new(buf))
+///     .unwrap()
+///     .finalize(false)
+///     .unwrap();
+/// assert_eq!(frame, &[0x5A, 0xA5, 5, 0x82, 0x60, 0x05, 0x00, 10]);
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Property<T> {
+    offset: u16,
+    ty: PhantomData<T>,
+}
+
+impl<T> Clone for Property<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Property<T> {}
+
+impl<T: Serialize> Property<T> {
+    /// Returns a new property at the given word offset.
+    pub const fn new(offset: u16) -> Self {
+        Self {
+            offset,
+            ty: PhantomData,
+        }
+    }
+
+    /// Returns the word offset of the property.
+    pub const fn offset(&self) -> u16 {
+        self.offset
+    }
+
+    /// Returns a new write request that changes the property of the control at `sp`.
+    pub fn request<S: Storage>(&self, sp: u16, value: &T, output: S) -> Result<Request<Write, S>> {
+        Request::word(output, sp.wrapping_add(self.offset), value)
+    }
+}
+
+/// Returns a new write request that changes the VP of the control at `sp`.
+///
+/// The VP is the first property of all the descriptors.
+pub fn set_vp<S: Storage>(sp: u16, vp: u16, output: S) -> Result<Request<Write, S>> {
+    DataVariable::VP.request(sp, &vp, output)
+}
+
+/// Returns a new write request that changes the position of the control at `sp`.
+///
+/// Applicable to [`DataVariable`], [`TextDisplay`] and [`Icon`].
+pub fn set_position<S: Storage>(sp: u16, position: Point, output: S) -> Result<Request<Write, S>> {
+    DataVariable::POSITION.request(sp, &position, output)
+}
+
+/// Returns a new write request that changes the color of the control at `sp`.
+///
+/// Applicable to [`DataVariable`] and [`TextDisplay`].
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::{color::Rgb565, request::Slice, sp};
+///
+/// let buf = &mut [0u8; 16];
+/// let frame = sp::set_color(0x6000, Rgb565::RED, Slice::new(buf))
+///     .unwrap()
+///     .finalize(false)
+///     .unwrap();
+/// assert_eq!(frame, &[0x5A, 0xA5, 5, 0x82, 0x60, 0x03, 0xF8, 0x00]);
+/// ```
+pub fn set_color<S: Storage>(sp: u16, color: Rgb565, output: S) -> Result<Request<Write, S>> {
+    DataVariable::COLOR.request(sp, &color, output)
+}
+
+/// SP block descriptor
+///
+/// Implemented by all the descriptors, which start with the VP property.
+pub trait Descriptor: Serialize {
+    /// Word length of the SP block, i.e. the distance between adjacent SP blocks
+    const WLEN: u8;
+
+    /// Returns a copy of the descriptor with the VP replaced.
+    fn with_vp(&self, vp: u16) -> Self;
+}
+
+macro_rules! impl_descriptor {
+    ($($ty:ident)+) => ($(
+        impl Descriptor for $ty {
+            const WLEN: u8 = $ty::WLEN;
+
+            fn with_vp(&self, vp: u16) -> Self {
+                Self { vp, ..self.clone() }
+            }
+        }
+    )+)
+}
+
+impl_descriptor! { DataVariable TextDisplay Icon BasicGraphics }
+
+/// Data variable display descriptor
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DataVariable {
+    /// VP
+    pub vp: u16,
+    /// Position of the top left corner
+    pub position: Point,
+    /// Text color
+    pub color: Rgb565,
+    /// Font library ID
+    pub font_lib: u8,
+    /// Font width in pixels
+    pub font_width: u8,
+    /// Alignment, 0x00 left, 0x01 right, 0x02 center
+    pub alignment: u8,
+    /// Number of integer digits
+    pub integer_digits: u8,
+    /// Number of decimal digits
+    pub decimal_digits: u8,
+    /// Variable data type
+    pub data_type: u8,
+}
+
+impl DataVariable {
+    /// Word length
+    pub const WLEN: u8 = 7;
+    /// VP
+    pub const VP: Property<u16> = Property::new(0x00);
+    /// Position of the top left corner
+    pub const POSITION: Property<Point> = Property::new(0x01);
+    /// Text color
+    pub const COLOR: Property<Rgb565> = Property::new(0x03);
+    /// Font library ID and font width
+    pub const FONT: Property<(u8, u8)> = Property::new(0x04);
+    /// Alignment and number of integer digits
+    pub const ALIGNMENT: Property<(u8, u8)> = Property::new(0x05);
+    /// Number of decimal digits and variable data type
+    pub const FORMAT: Property<(u8, u8)> = Property::new(0x06);
+}
+
+/// Text display descriptor
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TextDisplay {
+    /// VP
+    pub vp: u16,
+    /// Position of the top left corner
+    pub position: Point,
+    /// Text color
+    pub color: Rgb565,
+    /// Top left corner of the text box
+    pub top_left: Point,
+    /// Bottom right corner of the text box
+    pub bottom_right: Point,
+    /// Text length in bytes
+    pub len: u16,
+    /// ASCII font library ID
+    pub font0: u8,
+    /// Non-ASCII font library ID
+    pub font1: u8,
+    /// Font width in pixels
+    pub font_width: u8,
+    /// Font height in pixels
+    pub font_height: u8,
+    /// Encoding mode
+    pub encoding: u8,
+    /// Horizontal character spacing in pixels
+    pub h_spacing: u8,
+    /// Vertical line spacing in pixels, the high byte of its word
+    #[serde(with = "high_byte")]
+    pub v_spacing: u8,
+}
+
+/// Encodes a `u8` as the high byte of a word, the low byte is reserved.
+mod high_byte {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u8, serializer: S) -> Result<S::Ok, S::Error> {
+        (u16::from(*value) << 8).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u8, D::Error> {
+        u16::deserialize(deserializer).map(|word| (word >> 8) as u8)
+    }
+}
+
+impl TextDisplay {
+    /// Word length
+    pub const WLEN: u8 = 13;
+    /// VP
+    pub const VP: Property<u16> = Property::new(0x00);
+    /// Position of the top left corner
+    pub const POSITION: Property<Point> = Property::new(0x01);
+    /// Text color
+    pub const COLOR: Property<Rgb565> = Property::new(0x03);
+    /// Text box, top left and bottom right corners
+    pub const AREA: Property<(Point, Point)> = Property::new(0x04);
+    /// Text length in bytes
+    pub const LEN: Property<u16> = Property::new(0x08);
+    /// ASCII and non-ASCII font library IDs
+    pub const FONTS: Property<(u8, u8)> = Property::new(0x09);
+    /// Font width and height in pixels
+    pub const FONT_SIZE: Property<(u8, u8)> = Property::new(0x0A);
+    /// Encoding mode and horizontal spacing in pixels
+    pub const ENCODING: Property<(u8, u8)> = Property::new(0x0B);
+    /// Vertical spacing in pixels, the low byte is reserved
+    pub const V_SPACING: Property<(u8, Pad)> = Property::new(0x0C);
+}
+
+/// Variable icon descriptor
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Icon {
+    /// VP
+    pub vp: u16,
+    /// Position of the top left corner
+    pub position: Point,
+    /// Value displayed with the minimum icon
+    pub value_min: u16,
+    /// Value displayed with the maximum icon
+    pub value_max: u16,
+    /// Minimum icon ID
+    pub icon_min: u16,
+    /// Maximum icon ID
+    pub icon_max: u16,
+    /// Icon library ID
+    pub icon_lib: u8,
+    /// Display mode, 0x00 transparent background, 0x01 with background
+    pub mode: u8,
+}
+
+impl Icon {
+    /// Word length
+    pub const WLEN: u8 = 8;
+    /// VP
+    pub const VP: Property<u16> = Property::new(0x00);
+    /// Position of the top left corner
+    pub const POSITION: Property<Point> = Property::new(0x01);
+    /// Value displayed with the minimum icon
+    pub const VALUE_MIN: Property<u16> = Property::new(0x03);
+    /// Value displayed with the maximum icon
+    pub const VALUE_MAX: Property<u16> = Property::new(0x04);
+    /// Minimum icon ID
+    pub const ICON_MIN: Property<u16> = Property::new(0x05);
+    /// Maximum icon ID
+    pub const ICON_MAX: Property<u16> = Property::new(0x06);
+    /// Icon library ID and display mode
+    pub const ICON_LIB: Property<(u8, u8)> = Property::new(0x07);
+}
+
+/// Basic graphics descriptor
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BasicGraphics {
+    /// VP
+    pub vp: u16,
+    /// Top left corner of the drawing area
+    pub top_left: Point,
+    /// Bottom right corner of the drawing area
+    pub bottom_right: Point,
+}
+
+impl BasicGraphics {
+    /// Word length
+    pub const WLEN: u8 = 5;
+    /// VP
+    pub const VP: Property<u16> = Property::new(0x00);
+    /// Drawing area, top left and bottom right corners
+    pub const AREA: Property<(Point, Point)> = Property::new(0x01);
+}
+
+/// Control visibility
+///
+/// A control is hidden by writing `0xFF00` into the VP property of its SP block,
+/// and shown again by restoring its original VP.
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::{request::Slice, sp::Visibility};
+///
+/// let mut control = Visibility::new(0x6000, 0x5000);
+///
+/// let buf = &mut [0u8; 16];
+/// let frame = control.hide_request(Slice::new(buf)).unwrap().finalize(false).unwrap();
+/// assert_eq!(frame, &[0x5A, 0xA5, 5, 0x82, 0x60, 0x00, 0xFF, 0x00]);
+/// assert!(!control.is_visible());
+///
+/// let buf = &mut [0u8; 16];
+/// let frame = control.toggle_request(Slice::new(buf)).unwrap().finalize(false).unwrap();
+/// assert_eq!(frame, &[0x5A, 0xA5, 5, 0x82, 0x60, 0x00, 0x50, 0x00]);
+/// assert!(control.is_visible());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Visibility {
+    sp: u16,
+    vp: u16,
+    visible: bool,
+}
+
+impl Visibility {
+    /// VP value that hides the control
+    pub const HIDDEN: u16 = 0xFF00;
+
+    /// Returns a new visibility helper for the visible control at `sp` with the original `vp`.
+    pub const fn new(sp: u16, vp: u16) -> Self {
+        Self {
+            sp,
+            vp,
+            visible: true,
+        }
+    }
+
+    /// Returns the SP of the control.
+    pub const fn sp(&self) -> u16 {
+        self.sp
+    }
+
+    /// Returns the original VP of the control.
+    pub const fn vp(&self) -> u16 {
+        self.vp
+    }
+
+    /// Returns true if the control is visible, as of the last request.
+    pub const fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Returns a new write request that hides the control.
+    pub fn hide_request<S: Storage>(&mut self, output: S) -> Result<Request<Write, S>> {
+        self.request(false, output)
+    }
+
+    /// Returns a new write request that shows the control.
+    pub fn show_request<S: Storage>(&mut self, output: S) -> Result<Request<Write, S>> {
+        self.request(true, output)
+    }
+
+    /// Returns a new write request that toggles the visibility of the control.
+    pub fn toggle_request<S: Storage>(&mut self, output: S) -> Result<Request<Write, S>> {
+        self.request(!self.visible, output)
+    }
+
+    /// Returns a new write request that shows or hides the control.
+    pub fn request<S: Storage>(&mut self, visible: bool, output: S) -> Result<Request<Write, S>> {
+        let request = DataVariable::VP.request(self.sp, &self.value(visible), output)?;
+        self.visible = visible;
+        Ok(request)
+    }
+
+    /// Returns a new write request that shows or hides adjacent controls in one frame.
+    ///
+    /// The VP properties are a whole SP block apart, so the frame rewrites the SP blocks of the controls
+    /// with the given `descriptors`, with their VP replaced according to `visible`.
+    /// The SP of each control must follow the previous one by [`Descriptor::WLEN`] words.
+    /// Fails with [`Error::RequestBadParam`] if the controls aren't adjacent or don't match the descriptors one to one.
+    /// The controls are only updated if the request is built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use dguscard::{graphics::Point, request::Slice, sp::{Icon, Visibility}};
+    ///
+    /// let icon = |vp, x| Icon {
+    ///     vp,
+    ///     position: Point::new(x, 0),
+    ///     value_min: 0,
+    ///     value_max: 1,
+    ///     icon_min: 10,
+    ///     icon_max: 11,
+    ///     icon_lib: 0x30,
+    ///     mode: 0x00,
+    /// };
+    /// let descriptors = [icon(0x5000, 0), icon(0x5001, 40)];
+    /// let mut controls = [
+    ///     Visibility::new(0x6000, 0x5000),
+    ///     Visibility::new(0x6000 + Icon::WLEN as u16, 0x5001),
+    /// ];
+    ///
+    /// let buf = &mut [0u8; 64];
+    /// let frame = Visibility::batch_request(&mut controls, &descriptors, false, Slice::new(buf))
+    ///     .unwrap()
+    ///     .finalize(false)
+    ///     .unwrap();
+    /// assert_eq!(&frame[..8], &[0x5A, 0xA5, 35, 0x82, 0x60, 0x00, 0xFF, 0x00]);
+    /// assert_eq!(&frame[22..24], &[0xFF, 0x00]);
+    /// assert!(controls.iter().all(|control| !control.is_visible()));
+    /// ```
+    pub fn batch_request<D: Descriptor, S: Storage>(
+        controls: &mut [Visibility],
+        descriptors: &[D],
+        visible: bool,
+        output: S,
+    ) -> Result<Request<Write, S>> {
+        let Some(first) = controls.first() else {
+            return Err(Error::RequestBadParam);
+        };
+        if controls.len() != descriptors.len() {
+            return Err(Error::RequestBadParam);
+        }
+        let adjacent = controls
+            .iter()
+            .enumerate()
+            .all(|(i, control)| control.sp as usize == first.sp as usize + i * D::WLEN as usize);
+        if !adjacent {
+            return Err(Error::RequestBadParam);
+        }
+        let mut request = Request::new(
+            output,
+            Word {
+                addr: first.sp,
+                cmd: Write,
+            },
+        )?;
+        request.push_iter(
+            controls
+                .iter()
+                .zip(descriptors)
+                .map(|(control, descriptor)| descriptor.with_vp(control.value(visible))),
+        )?;
+        controls
+            .iter_mut()
+            .for_each(|control| control.visible = visible);
+        Ok(request)
+    }
+
+    /// Returns the VP property value for the given visibility.
+    const fn value(&self, visible: bool) -> u16 {
+        if visible {
+            self.vp
+        } else {
+            Self::HIDDEN
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::Slice;
+
+    #[test]
+    fn text_layout() {
+        let text = TextDisplay {
+            vp: 0x5000,
+            position: Point::new(1, 2),
+            color: Rgb565::WHITE,
+            top_left: Point::new(3, 4),
+            bottom_right: Point::new(5, 6),
+            len: 7,
+            font0: 8,
+            font1: 9,
+            font_width: 10,
+            font_height: 11,
+            encoding: 12,
+            h_spacing: 13,
+            v_spacing: 14,
+        };
+        let buf = &mut [0u8; 64];
+        let mut request = Request::with_slice(
+            buf,
+            Word {
+                addr: 0x6000,
+                cmd: Write,
+            },
+        )
+        .unwrap();
+        request.push(&text).unwrap();
+        let frame = request.finalize(false).unwrap();
+        let words = &frame[6..];
+        assert_eq!(words.len(), TextDisplay::WLEN as usize * 2);
+        let at = |property: u16| &words[property as usize * 2..][..2];
+        assert_eq!(at(TextDisplay::VP.offset()), &[0x50, 0x00]);
+        assert_eq!(at(TextDisplay::POSITION.offset()), &[0, 1]);
+        assert_eq!(at(TextDisplay::COLOR.offset()), &[0xFF, 0xFF]);
+        assert_eq!(at(TextDisplay::AREA.offset()), &[0, 3]);
+        assert_eq!(at(TextDisplay::LEN.offset()), &[0, 7]);
+        assert_eq!(at(TextDisplay::FONTS.offset()), &[8, 9]);
+        assert_eq!(at(TextDisplay::FONT_SIZE.offset()), &[10, 11]);
+        assert_eq!(at(TextDisplay::ENCODING.offset()), &[12, 13]);
+        assert_eq!(at(TextDisplay::V_SPACING.offset()), &[14, 0]);
+
+        let buf = &mut [0u8; 16];
+        let frame = TextDisplay::V_SPACING
+            .request(0x6000, &(14, Pad), Slice::new(buf))
+            .unwrap()
+            .finalize(false)
+            .unwrap();
+        assert_eq!(frame, &[0x5A, 0xA5, 5, 0x82, 0x60, 0x0C, 14, 0]);
+    }
+
+    #[test]
+    fn set_position_request() {
+        let buf = &mut [0u8; 16];
+        let frame = set_position(0x6000, Point::new(0x0120, 0x0040), Slice::new(buf))
+            .unwrap()
+            .finalize(false)
+            .unwrap();
+        assert_eq!(
+            frame,
+            &[0x5A, 0xA5, 7, 0x82, 0x60, 0x01, 0x01, 0x20, 0x00, 0x40]
+        );
+    }
+
+    #[test]
+    fn area_request() {
+        let buf = &mut [0u8; 16];
+        let frame = BasicGraphics::AREA
+            .request(
+                0x6000,
+                &(Point::new(0, 0), Point::new(0x01DF, 0x010F)),
+                Slice::new(buf),
+            )
+            .unwrap()
+            .finalize(false)
+            .unwrap();
+        assert_eq!(
+            frame,
+            &[0x5A, 0xA5, 11, 0x82, 0x60, 0x01, 0, 0, 0, 0, 0x01, 0xDF, 0x01, 0x0F]
+        );
+    }
+
+    #[test]
+    fn batch() {
+        let icon = |vp| Icon {
+            vp,
+            position: Point::new(0x10, 0x20),
+            value_min: 0,
+            value_max: 1,
+            icon_min: 10,
+            icon_max: 11,
+            icon_lib: 0x30,
+            mode: 0x01,
+        };
+        let descriptors = [icon(0x5000), icon(0x5010), icon(0x5020)];
+        let sp = |i: u16| 0x6000 + i * Icon::WLEN as u16;
+        let mut controls = [
+            Visibility::new(sp(0), 0x5000),
+            Visibility::new(sp(1), 0x5010),
+            Visibility::new(sp(2), 0x5020),
+        ];
+
+        let buf = &mut [0u8; 64];
+        let frame = Visibility::batch_request(&mut controls, &descriptors, false, Slice::new(buf))
+            .unwrap()
+            .finalize(false)
+            .unwrap();
+        assert_eq!(&frame[..6], &[0x5A, 0xA5, 51, 0x82, 0x60, 0x00]);
+        let blocks: [&[u8]; 3] = [&frame[6..22], &frame[22..38], &frame[38..54]];
+        for block in blocks {
+            assert_eq!(
+                block,
+                &[0xFF, 0x00, 0, 0x10, 0, 0x20, 0, 0, 0, 1, 0, 10, 0, 11, 0x30, 0x01]
+            );
+        }
+        assert!(controls.iter().all(|control| !control.is_visible()));
+
+        let buf = &mut [0u8; 64];
+        let frame =
+            Visibility::batch_request(&mut controls[1..], &descriptors[1..], true, Slice::new(buf))
+                .unwrap()
+                .finalize(false)
+                .unwrap();
+        assert_eq!(&frame[..8], &[0x5A, 0xA5, 35, 0x82, 0x60, 0x08, 0x50, 0x10]);
+        assert_eq!(&frame[22..24], &[0x50, 0x20]);
+        assert!(!controls[0].is_visible());
+        assert!(controls[1].is_visible());
+        assert!(controls[2].is_visible());
+    }
+
+    #[test]
+    fn batch_bad_param() {
+        let icon = Icon {
+            vp: 0x5000,
+            position: Point::new(0, 0),
+            value_min: 0,
+            value_max: 1,
+            icon_min: 0,
+            icon_max: 1,
+            icon_lib: 0,
+            mode: 0,
+        };
+        let descriptors = [icon.clone(), icon.clone()];
+        // Only the VP words are adjacent
+        let mut controls = [
+            Visibility::new(0x6000, 0x5000),
+            Visibility::new(0x6001, 0x5001),
+        ];
+        let buf = &mut [0u8; 64];
+        assert_eq!(
+            Visibility::batch_request(&mut controls, &descriptors, false, Slice::new(buf)).err(),
+            Some(Error::RequestBadParam)
+        );
+        let mut controls = [
+            Visibility::new(0x6000, 0x5000),
+            Visibility::new(0x6000 + 2 * Icon::WLEN as u16, 0x5001),
+        ];
+        assert_eq!(
+            Visibility::batch_request(&mut controls, &descriptors, false, Slice::new(buf)).err(),
+            Some(Error::RequestBadParam)
+        );
+        assert_eq!(
+            Visibility::batch_request(&mut controls[..1], &descriptors, false, Slice::new(buf))
+                .err(),
+            Some(Error::RequestBadParam)
+        );
+        assert_eq!(
+            Visibility::batch_request::<Icon, _>(&mut [], &[], false, Slice::new(buf)).err(),
+            Some(Error::RequestBadParam)
+        );
+        assert!(controls.iter().all(|control| control.is_visible()));
+    }
+}
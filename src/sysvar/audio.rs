@@ -0,0 +1,160 @@
+use crate::{
+    command::Write,
+    request::{Request, Storage},
+    Error, Result,
+};
+use serde::{Serialize, Serializer};
+
+/// WAE music playback
+///
+/// Located at the system variable `0x00A0`, 2 words.
+/// Plays `count` consecutive WAE segments starting from `segment`.
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::{request::Slice, sysvar::Music};
+///
+/// let buf = &mut [0u8; 16];
+/// let frame = Music::play(3, 1, Music::MAX_VOLUME)
+///     .unwrap()
+///     .request(Slice::new(buf))
+///     .unwrap()
+///     .finalize(false)
+///     .unwrap();
+/// assert_eq!(frame, &[0x5A, 0xA5, 7, 0x82, 0x00, 0xA0, 3, 1, 0x40, 0x00]);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Music {
+    segment: u8,
+    count: u8,
+    volume: u8,
+}
+
+impl Music {
+    /// Start address
+    pub const ADDR: u16 = 0x00A0;
+    /// Word length
+    pub const WLEN: u8 = 2;
+    /// Maximum volume, i.e. 100%
+    pub const MAX_VOLUME: u8 = 0x40;
+
+    /// Returns a new playback of `count` segments starting from `segment` at `volume`.
+    ///
+    /// Fails with [`Error::RequestBadParam`] if `count` is zero or `volume` is above [`Music::MAX_VOLUME`].
+    pub const fn play(segment: u8, count: u8, volume: u8) -> Result<Self> {
+        if count == 0 || volume > Self::MAX_VOLUME {
+            return Err(Error::RequestBadParam);
+        }
+        Ok(Self {
+            segment,
+            count,
+            volume,
+        })
+    }
+
+    /// Returns a new playback that stops the music.
+    pub const fn stop() -> Self {
+        Self {
+            segment: 0,
+            count: 0,
+            volume: 0,
+        }
+    }
+
+    /// Returns a new write request containing the playback.
+    pub fn request<S: Storage>(&self, output: S) -> Result<Request<Write, S>> {
+        Request::word(output, Self::ADDR, self)
+    }
+}
+
+impl Serialize for Music {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (self.segment, self.count, self.volume, 0u8).serialize(serializer)
+    }
+}
+
+/// Buzzer beep
+///
+/// Located at the system variable `0x00A0`, 1 word.
+/// The duration is encoded in 8 ms ticks.
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::{request::Slice, sysvar::Beep};
+///
+/// let buf = &mut [0u8; 16];
+/// let frame = Beep::ms(200)
+///     .unwrap()
+///     .request(Slice::new(buf))
+///     .unwrap()
+///     .finalize(false)
+///     .unwrap();
+/// assert_eq!(frame, &[0x5A, 0xA5, 5, 0x82, 0x00, 0xA0, 0x00, 25]);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Beep(u16);
+
+impl Beep {
+    /// Start address
+    pub const ADDR: u16 = 0x00A0;
+    /// Word length
+    pub const WLEN: u8 = 1;
+    /// Duration of a single tick in milliseconds
+    pub const TICK_MS: u32 = 8;
+    /// Maximum duration in milliseconds
+    pub const MAX_MS: u32 = u16::MAX as u32 * Self::TICK_MS;
+
+    /// Returns a new beep for the given duration in milliseconds, rounded up to the next tick.
+    ///
+    /// Fails with [`Error::RequestBadParam`] if `ms` is zero or above [`Beep::MAX_MS`].
+    pub const fn ms(ms: u32) -> Result<Self> {
+        if ms == 0 || ms > Self::MAX_MS {
+            return Err(Error::RequestBadParam);
+        }
+        Ok(Self(ms.div_ceil(Self::TICK_MS) as u16))
+    }
+
+    /// Returns a new write request containing the beep.
+    pub fn request<S: Storage>(&self, output: S) -> Result<Request<Write, S>> {
+        Request::word(output, Self::ADDR, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::Slice;
+
+    #[test]
+    fn play_bad_param() {
+        assert_eq!(Err(Error::RequestBadParam), Music::play(0, 0, 0x20));
+        assert_eq!(Err(Error::RequestBadParam), Music::play(0, 1, 0x41));
+    }
+
+    #[test]
+    fn stop() {
+        let buf = &mut [0u8; 16];
+        let frame = Music::stop()
+            .request(Slice::new(buf))
+            .unwrap()
+            .finalize(false)
+            .unwrap();
+        assert_eq!(frame, &[0x5A, 0xA5, 7, 0x82, 0x00, 0xA0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn beep_ticks() {
+        assert_eq!(Ok(Beep(1)), Beep::ms(1));
+        assert_eq!(Ok(Beep(2)), Beep::ms(9));
+        assert_eq!(Ok(Beep(u16::MAX)), Beep::ms(Beep::MAX_MS));
+        assert_eq!(Err(Error::RequestBadParam), Beep::ms(0));
+        assert_eq!(Err(Error::RequestBadParam), Beep::ms(Beep::MAX_MS + 1));
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{
+    command::{Read, Word},
+    request::{Request, Storage},
+    response::Response,
+    Error, Result,
+};
+use serde::{Deserialize, Deserializer};
+
+/// Unique device ID (serial) of the T5L chip
+///
+/// Located at the system variable `0x0000`, 4 words.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DeviceId(pub u64);
+
+impl DeviceId {
+    /// Start address
+    pub const ADDR: u16 = 0x0000;
+    /// Word length
+    pub const WLEN: u8 = 4;
+}
+
+/// Software version
+///
+/// Located at the system variable `0x000F`, 1 word.
+/// The versions are returned as reported by the device, e.g. `0x45` for V4.5.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Version {
+    /// DGUS GUI core version
+    pub gui: u8,
+    /// OS core version
+    pub os: u8,
+}
+
+impl Version {
+    /// Start address
+    pub const ADDR: u16 = 0x000F;
+    /// Word length
+    pub const WLEN: u8 = 1;
+}
+
+/// Device identification block
+///
+/// Decoded from the system variables `0x0000..=0x000F`.
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::{request::Slice, response::Response, sysvar::DeviceInfo};
+/// // Build the read request for the whole identification block.
+/// let buf = &mut [0u8; 16];
+/// let frame = DeviceInfo::request(Slice::new(buf)).unwrap().finalize(true).unwrap();
+/// assert_eq!(frame, &[0x5A, 0xA5, 6, 0x83, 0x00, 0x00, 16, 0x28, 0x6C]);
+///
+/// // Decode the identification block from the response.
+/// # let rx = &[
+/// #     0x5A, 0xA5, 36, 0x83, 0x00, 0x00, 16,
+/// #     0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF,
+/// #     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+/// #     0x45, 0x21,
+/// # ];
+/// let response = Response::from_bytes(rx, false).unwrap();
+/// let info = DeviceInfo::from_response(response).unwrap();
+/// assert_eq!(info.gui_version, 0x45);
+/// assert_eq!(info.os_version, 0x21);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DeviceInfo {
+    /// Unique device ID
+    pub id: DeviceId,
+    /// DGUS GUI core version
+    pub gui_version: u8,
+    /// OS core version
+    pub os_version: u8,
+}
+
+impl DeviceInfo {
+    /// Start address
+    pub const ADDR: u16 = DeviceId::ADDR;
+    /// Word length
+    pub const WLEN: u8 = (Version::ADDR - DeviceId::ADDR) as u8 + Version::WLEN;
+
+    /// Returns a new read request for the whole identification block.
+    pub fn request<S: Storage>(output: S) -> Result<Request<Read, S>> {
+        Request::new(
+            output,
+            Word {
+                addr: Self::ADDR,
+                cmd: Read { wlen: Self::WLEN },
+            },
+        )
+    }
+
+    /// Decodes the identification block from a [`Response::WordData`].
+    ///
+    /// The response must start at [`DeviceInfo::ADDR`] and contain at least [`DeviceInfo::WLEN`] words.
+    pub fn from_response(response: Response) -> Result<Self> {
+        let Response::WordData { cmd, mut content } = response else {
+            return Err(Error::ResponseUnexpected);
+        };
+        if cmd.addr != Self::ADDR || cmd.cmd.wlen < Self::WLEN {
+            return Err(Error::ResponseUnexpected);
+        }
+        content.take()
+    }
+}
+
+impl<'de> Deserialize<'de> for DeviceInfo {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // Reserved words between the device ID and the version
+        type Reserved = [u16; (Version::ADDR - DeviceId::ADDR) as usize - DeviceId::WLEN as usize];
+        let (id, _, version) = <(DeviceId, Reserved, Version)>::deserialize(deserializer)?;
+        Ok(Self {
+            id,
+            gui_version: version.gui,
+            os_version: version.os,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{request::Slice, response::word_data};
+
+    #[test]
+    fn version() {
+        let input = [0x5A, 0xA5, 6, 0x83, 0x00, 0x0F, 1, 0x45, 0x21];
+        let (cmd, mut content) = word_data(&input);
+        assert_eq!(cmd.addr, Version::ADDR);
+        assert_eq!(
+            Version {
+                gui: 0x45,
+                os: 0x21
+            },
+            content.take().unwrap()
+        );
+    }
+
+    #[test]
+    fn info() {
+        let mut input = [0u8; 39];
+        input[..7].copy_from_slice(&[0x5A, 0xA5, 36, 0x83, 0x00, 0x00, 16]);
+        input[7..15].copy_from_slice(&0x0123_4567_89AB_CDEF_u64.to_be_bytes());
+        input[37..].copy_from_slice(&[0x45, 0x21]);
+        let response = Response::from_bytes(&input, false).unwrap();
+        assert_eq!(
+            DeviceInfo {
+                id: DeviceId(0x0123_4567_89AB_CDEF),
+                gui_version: 0x45,
+                os_version: 0x21,
+            },
+            DeviceInfo::from_response(response).unwrap()
+        );
+    }
+
+    #[test]
+    fn info_short() {
+        let input = [0x5A, 0xA5, 6, 0x83, 0x00, 0x0F, 1, 0x45, 0x21];
+        let response = Response::from_bytes(&input, false).unwrap();
+        assert_eq!(
+            Err(Error::ResponseUnexpected),
+            DeviceInfo::from_response(response)
+        );
+    }
+
+    #[test]
+    fn info_request() {
+        let buf = &mut [0u8; 16];
+        let frame = DeviceInfo::request(Slice::new(buf))
+            .unwrap()
+            .finalize(false)
+            .unwrap();
+        assert_eq!(frame, &[0x5A, 0xA5, 4, 0x83, 0x00, 0x00, 16]);
+    }
+}
@@ -0,0 +1,15 @@
+//! System variables
+//!
+//! Typed access to the T5L system variable area, i.e. the reserved VP addresses `0x0000..0x1000`.
+
+mod audio;
+mod device;
+mod nor;
+mod touch;
+
+pub use self::audio::{Beep, Music};
+pub use self::device::{DeviceId, DeviceInfo, Version};
+pub use self::nor::{NorAccess, NorChunks, NorMode};
+pub use self::touch::{
+    TouchAction, TouchEvent, TouchFrames, TouchScript, TouchSimulation, TouchStatus,
+};
@@ -0,0 +1,253 @@
+use crate::{
+    command::{Read, Word, Write},
+    request::{Request, Storage},
+    response::Response,
+    Error, Result,
+};
+use serde::{Serialize, Serializer};
+
+/// NOR flash access direction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum NorMode {
+    /// Copy from NOR flash into VP memory
+    Read,
+    /// Copy from VP memory into NOR flash
+    Write,
+}
+
+/// NOR flash database access
+///
+/// Located at the system variable `0x0008`, 4 words.
+/// Copies `wlen` words between the NOR flash database and VP memory.
+/// The device clears the mode byte once the copy is done, which can be polled with [`NorAccess::status_request`].
+/// All addresses and the length must be even.
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::{request::Slice, response::Response, sysvar::NorAccess};
+///
+/// // Persist 0x300 words from VP 0x5000 into the flash address 0x1000.
+/// let access = NorAccess::write(0x1000, 0x5000, 0x300).unwrap();
+/// // Split the transfer into 256 word chunks.
+/// for chunk in access.chunks(0x100).unwrap() {
+///     let buf = &mut [0u8; 16];
+///     let frame = chunk.request(Slice::new(buf)).unwrap().finalize(true).unwrap();
+///     // Transmit the frame.
+///
+///     // Poll the status until the copy is done.
+///     let buf = &mut [0u8; 16];
+///     let frame = NorAccess::status_request(Slice::new(buf)).unwrap().finalize(true).unwrap();
+///     // Transmit the frame and receive the response.
+/// # let rx = &[0x5A, 0xA5, 6, 0x83, 0x00, 0x08, 1, 0x00, 0x00];
+///     let response = Response::from_bytes(rx, false).unwrap();
+///     assert!(NorAccess::is_done(response).unwrap());
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct NorAccess {
+    mode: NorMode,
+    flash_addr: u32,
+    vp: u16,
+    wlen: u16,
+}
+
+impl NorAccess {
+    /// Start address
+    pub const ADDR: u16 = 0x0008;
+    /// Word length
+    pub const WLEN: u8 = 4;
+    /// Maximum NOR flash address, 24 bits
+    pub const MAX_FLASH_ADDR: u32 = 0x00FF_FFFF;
+
+    /// Returns a new access reading `wlen` words from `flash_addr` into `vp`.
+    ///
+    /// Fails with [`Error::RequestBadParam`] if any of the parameters are odd or out of range.
+    pub const fn read(flash_addr: u32, vp: u16, wlen: u16) -> Result<Self> {
+        Self::new(NorMode::Read, flash_addr, vp, wlen)
+    }
+
+    /// Returns a new access writing `wlen` words from `vp` into `flash_addr`.
+    ///
+    /// Fails with [`Error::RequestBadParam`] if any of the parameters are odd or out of range.
+    pub const fn write(flash_addr: u32, vp: u16, wlen: u16) -> Result<Self> {
+        Self::new(NorMode::Write, flash_addr, vp, wlen)
+    }
+
+    const fn new(mode: NorMode, flash_addr: u32, vp: u16, wlen: u16) -> Result<Self> {
+        if flash_addr % 2 != 0 || vp % 2 != 0 || wlen % 2 != 0 || wlen == 0 {
+            return Err(Error::RequestBadParam);
+        }
+        if flash_addr > Self::MAX_FLASH_ADDR - (wlen as u32 - 1) {
+            return Err(Error::RequestBadParam);
+        }
+        if vp as u32 + wlen as u32 - 1 > u16::MAX as u32 {
+            return Err(Error::RequestBadParam);
+        }
+        Ok(Self {
+            mode,
+            flash_addr,
+            vp,
+            wlen,
+        })
+    }
+
+    /// Returns an iterator splitting the access into consecutive accesses of at most `max_wlen` words.
+    ///
+    /// Each chunk must be completed before the next one is requested.
+    /// An odd `max_wlen` is rounded down to keep the chunks even.
+    /// Fails with [`Error::RequestBadParam`] if `max_wlen` is less than 2.
+    pub fn chunks(self, max_wlen: u16) -> Result<NorChunks> {
+        if max_wlen < 2 {
+            return Err(Error::RequestBadParam);
+        }
+        Ok(NorChunks {
+            access: self,
+            max_wlen: max_wlen & !1,
+        })
+    }
+
+    /// Returns a new write request containing the access command.
+    pub fn request<S: Storage>(&self, output: S) -> Result<Request<Write, S>> {
+        Request::word(output, Self::ADDR, self)
+    }
+
+    /// Returns a new read request for the access status.
+    pub fn status_request<S: Storage>(output: S) -> Result<Request<Read, S>> {
+        Request::new(
+            output,
+            Word {
+                addr: Self::ADDR,
+                cmd: Read { wlen: 1 },
+            },
+        )
+    }
+
+    /// Decodes the access status from a [`Response::WordData`].
+    ///
+    /// Returns true if the device has completed the last access.
+    pub fn is_done(response: Response) -> Result<bool> {
+        let Response::WordData { cmd, mut content } = response else {
+            return Err(Error::ResponseUnexpected);
+        };
+        if cmd.addr != Self::ADDR || cmd.cmd.wlen < 1 {
+            return Err(Error::ResponseUnexpected);
+        }
+        let (mode, _): (u8, u8) = content.take()?;
+        Ok(mode == 0)
+    }
+}
+
+impl Serialize for NorAccess {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mode: u32 = match self.mode {
+            NorMode::Read => 0x5A,
+            NorMode::Write => 0xA5,
+        };
+        (mode << 24 | self.flash_addr, self.vp, self.wlen).serialize(serializer)
+    }
+}
+
+/// Iterator over the chunks of a [`NorAccess`]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct NorChunks {
+    access: NorAccess,
+    max_wlen: u16,
+}
+
+impl Iterator for NorChunks {
+    type Item = NorAccess;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.access.wlen == 0 {
+            return None;
+        }
+        let wlen = self.access.wlen.min(self.max_wlen);
+        let chunk = NorAccess {
+            wlen,
+            ..self.access
+        };
+        self.access.flash_addr += wlen as u32;
+        self.access.vp = self.access.vp.wrapping_add(wlen);
+        self.access.wlen -= wlen;
+        Some(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::Slice;
+
+    #[test]
+    fn read_request() {
+        let buf = &mut [0u8; 16];
+        let frame = NorAccess::read(0x01_2344, 0x5000, 0x0010)
+            .unwrap()
+            .request(Slice::new(buf))
+            .unwrap()
+            .finalize(false)
+            .unwrap();
+        assert_eq!(
+            frame,
+            &[0x5A, 0xA5, 11, 0x82, 0x00, 0x08, 0x5A, 0x01, 0x23, 0x44, 0x50, 0x00, 0x00, 0x10]
+        );
+    }
+
+    #[test]
+    fn bad_param() {
+        assert_eq!(Err(Error::RequestBadParam), NorAccess::read(1, 0, 2));
+        assert_eq!(Err(Error::RequestBadParam), NorAccess::read(0, 1, 2));
+        assert_eq!(Err(Error::RequestBadParam), NorAccess::read(0, 0, 3));
+        assert_eq!(Err(Error::RequestBadParam), NorAccess::read(0, 0, 0));
+        assert_eq!(
+            Err(Error::RequestBadParam),
+            NorAccess::write(0x00FF_FFFE, 0, 4)
+        );
+        assert_eq!(Err(Error::RequestBadParam), NorAccess::write(0, 0xFFFE, 4));
+    }
+
+    #[test]
+    fn chunks() {
+        let mut chunks = NorAccess::write(0x1000, 0x5000, 10)
+            .unwrap()
+            .chunks(5)
+            .unwrap();
+        assert_eq!(
+            chunks.next(),
+            Some(NorAccess::write(0x1000, 0x5000, 4).unwrap())
+        );
+        assert_eq!(
+            chunks.next(),
+            Some(NorAccess::write(0x1004, 0x5004, 4).unwrap())
+        );
+        assert_eq!(
+            chunks.next(),
+            Some(NorAccess::write(0x1008, 0x5008, 2).unwrap())
+        );
+        assert_eq!(chunks.next(), None);
+        assert_eq!(
+            NorAccess::write(0x1000, 0x5000, 10)
+                .unwrap()
+                .chunks(1)
+                .err(),
+            Some(Error::RequestBadParam)
+        );
+    }
+
+    #[test]
+    fn status() {
+        let input = [0x5A, 0xA5, 6, 0x83, 0x00, 0x08, 1, 0xA5, 0x01];
+        let response = Response::from_bytes(&input, false).unwrap();
+        assert!(!NorAccess::is_done(response).unwrap());
+        let input = [0x5A, 0xA5, 6, 0x83, 0x00, 0x09, 1, 0x00, 0x00];
+        let response = Response::from_bytes(&input, false).unwrap();
+        assert_eq!(Err(Error::ResponseUnexpected), NorAccess::is_done(response));
+    }
+}
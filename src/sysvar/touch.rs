@@ -0,0 +1,462 @@
+use crate::{
+    command::{Read, Word, Write},
+    request::{FrameSource, Frames, Request, Storage},
+    response::Response,
+    Error, Result,
+};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Simulated touch action
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TouchAction {
+    /// Press down
+    Press,
+    /// Lift up
+    Release,
+    /// Keep pressing
+    Hold,
+}
+
+impl Serialize for TouchAction {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u16(match self {
+            TouchAction::Press => 0x0001,
+            TouchAction::Release => 0x0002,
+            TouchAction::Hold => 0x0003,
+        })
+    }
+}
+
+/// Touch simulation
+///
+/// Located at the system variable `0x00D4`, 4 words.
+/// The device clears the enable word once the simulated touch is processed.
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::{request::Slice, sysvar::TouchSimulation};
+///
+/// let buf = &mut [0u8; 20];
+/// let frame = TouchSimulation::press(100, 200)
+///     .request(Slice::new(buf))
+///     .unwrap()
+///     .finalize(false)
+///     .unwrap();
+/// assert_eq!(
+///     frame,
+///     &[0x5A, 0xA5, 11, 0x82, 0x00, 0xD4, 0x5A, 0xA5, 0x00, 0x01, 0x00, 100, 0x00, 200]
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TouchSimulation {
+    /// Action
+    pub action: TouchAction,
+    /// X coordinate
+    pub x: u16,
+    /// Y coordinate
+    pub y: u16,
+}
+
+impl TouchSimulation {
+    /// Start address
+    pub const ADDR: u16 = 0x00D4;
+    /// Word length
+    pub const WLEN: u8 = 4;
+    /// Enable word, triggers the simulation
+    const ENABLE: u16 = 0x5AA5;
+
+    /// Returns a new press simulation.
+    pub const fn press(x: u16, y: u16) -> Self {
+        Self {
+            action: TouchAction::Press,
+            x,
+            y,
+        }
+    }
+
+    /// Returns a new release simulation.
+    pub const fn release(x: u16, y: u16) -> Self {
+        Self {
+            action: TouchAction::Release,
+            x,
+            y,
+        }
+    }
+
+    /// Returns a new hold simulation.
+    pub const fn hold(x: u16, y: u16) -> Self {
+        Self {
+            action: TouchAction::Hold,
+            x,
+            y,
+        }
+    }
+
+    /// Returns a new write request containing the simulation.
+    pub fn request<S: Storage>(&self, output: S) -> Result<Request<Write, S>> {
+        Request::word(output, Self::ADDR, self)
+    }
+}
+
+impl Serialize for TouchSimulation {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (Self::ENABLE, self.action, self.x, self.y).serialize(serializer)
+    }
+}
+
+/// Scripted touch sequence
+///
+/// Iterates over the [`TouchSimulation`] steps of a gesture.
+/// The steps should be sent with a delay in between, so the device can process each of them.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "heapless")] {
+/// use dguscard::{request::HVec, sysvar::TouchScript};
+///
+/// for frame in TouchScript::drag((0, 0), (100, 50), 4).frames::<HVec<16>>(true) {
+///     let frame = frame.unwrap();
+///     // Transmit the frame and wait for the device to process it.
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TouchScript {
+    from: (u16, u16),
+    to: (u16, u16),
+    holds: u16,
+    step: u32,
+}
+
+impl TouchScript {
+    /// Returns a new tap gesture, i.e. press and release.
+    pub const fn tap(x: u16, y: u16) -> Self {
+        Self::new((x, y), (x, y), 0)
+    }
+
+    /// Returns a new long press gesture, i.e. press, hold for the given number of steps, and release.
+    pub const fn long_press(x: u16, y: u16, holds: u16) -> Self {
+        Self::new((x, y), (x, y), holds)
+    }
+
+    /// Returns a new drag gesture.
+    ///
+    /// Presses at `from`, holds through the given number of interpolated steps, and releases at `to`.
+    pub const fn drag(from: (u16, u16), to: (u16, u16), steps: u16) -> Self {
+        Self::new(from, to, steps)
+    }
+
+    const fn new(from: (u16, u16), to: (u16, u16), holds: u16) -> Self {
+        Self {
+            from,
+            to,
+            holds,
+            step: 0,
+        }
+    }
+
+    /// Returns an iterator over the finalized frames of the gesture.
+    pub fn frames<S: Storage + Default>(self, crc: bool) -> TouchFrames<S> {
+        Frames::from_source(self, crc)
+    }
+
+    /// Interpolates a coordinate between `from` and `to` for the current step.
+    fn interpolate(&self, from: u16, to: u16) -> u16 {
+        let (from, to) = (from as i64, to as i64);
+        let span = self.holds as i64 + 1;
+        (from + (to - from) * self.step as i64 / span) as u16
+    }
+}
+
+impl Iterator for TouchScript {
+    type Item = TouchSimulation;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.holds as u32 + 1;
+        let step = match self.step {
+            0 => TouchSimulation::press(self.from.0, self.from.1),
+            step if step < last => TouchSimulation::hold(
+                self.interpolate(self.from.0, self.to.0),
+                self.interpolate(self.from.1, self.to.1),
+            ),
+            step if step == last => TouchSimulation::release(self.to.0, self.to.1),
+            _ => return None,
+        };
+        self.step += 1;
+        Some(step)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.holds as usize + 2).saturating_sub(self.step as usize);
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for TouchScript {}
+
+impl FrameSource for TouchScript {
+    fn next_request<S: Storage>(&mut self, output: S) -> Option<Result<Request<Write, S>>> {
+        self.next().map(|step| step.request(output))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        Iterator::size_hint(self)
+    }
+}
+
+/// Iterator over the finalized frames of a [`TouchScript`]
+pub type TouchFrames<S> = Frames<TouchScript, S>;
+
+/// Touch event reported by the touch panel
+///
+/// Decoded from a single byte.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TouchEvent {
+    /// No touch
+    None,
+    /// Pressed down
+    Press,
+    /// Lifted up
+    Release,
+    /// Pressing continuously
+    Hold,
+}
+
+impl<'de> Deserialize<'de> for TouchEvent {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(match u8::deserialize(deserializer)? {
+            0x00 => TouchEvent::None,
+            0x01 => TouchEvent::Press,
+            0x02 => TouchEvent::Release,
+            0x03 => TouchEvent::Hold,
+            v => {
+                return Err(de::Error::invalid_value(
+                    de::Unexpected::Unsigned(v as u64),
+                    &"a touch event between 0x00 and 0x03",
+                ))
+            }
+        })
+    }
+}
+
+/// Touch panel status
+///
+/// Located at the system variable `0x0016`, 3 words.
+/// The device sets the update flag on every touch event, it is up to the user to clear it.
+///
+/// # Example
+///
+/// ```rust
+/// use dguscard::{request::Slice, response::Response, sysvar::{TouchEvent, TouchStatus}};
+///
+/// // Poll the touch status
+/// let buf = &mut [0u8; 16];
+/// let frame = TouchStatus::request(Slice::new(buf)).unwrap().finalize(false).unwrap();
+/// assert_eq!(frame, &[0x5A, 0xA5, 4, 0x83, 0x00, 0x16, 3]);
+///
+/// # let rx = &[0x5A, 0xA5, 10, 0x83, 0x00, 0x16, 3, 0x5A, 0x01, 0x00, 100, 0x00, 200];
+/// let response = Response::from_bytes(rx, false).unwrap();
+/// let buf = &mut [0u8; 16];
+/// if let Some((status, clear)) = TouchStatus::poll(response, Slice::new(buf)).unwrap() {
+///     assert_eq!(status.event, TouchEvent::Press);
+///     assert_eq!((status.x, status.y), (100, 200));
+///     // Acknowledge the event by clearing the update flag.
+///     let frame = clear.finalize(false).unwrap();
+///     assert_eq!(frame, &[0x5A, 0xA5, 5, 0x82, 0x00, 0x16, 0x00, 0x00]);
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TouchStatus {
+    /// The status is updated since the flag was last cleared
+    pub updated: bool,
+    /// Event
+    pub event: TouchEvent,
+    /// X coordinate
+    pub x: u16,
+    /// Y coordinate
+    pub y: u16,
+}
+
+impl TouchStatus {
+    /// Start address
+    pub const ADDR: u16 = 0x0016;
+    /// Word length
+    pub const WLEN: u8 = 3;
+    /// Update flag, set by the device
+    const UPDATED: u8 = 0x5A;
+
+    /// Returns a new read request for the touch status.
+    pub fn request<S: Storage>(output: S) -> Result<Request<Read, S>> {
+        Request::new(
+            output,
+            Word {
+                addr: Self::ADDR,
+                cmd: Read { wlen: Self::WLEN },
+            },
+        )
+    }
+
+    /// Returns a new write request that clears the update flag.
+    pub fn clear_request<S: Storage>(output: S) -> Result<Request<Write, S>> {
+        Request::word(output, Self::ADDR, &0u16)
+    }
+
+    /// Decodes the touch status from a [`Response::WordData`].
+    ///
+    /// Returns the status along with a request clearing the update flag,
+    /// or [`None`] if the status is not updated since the last clear.
+    pub fn poll<S: Storage>(
+        response: Response,
+        output: S,
+    ) -> Result<Option<(Self, Request<Write, S>)>> {
+        let Response::WordData { cmd, mut content } = response else {
+            return Err(Error::ResponseUnexpected);
+        };
+        if cmd.addr != Self::ADDR || cmd.cmd.wlen < Self::WLEN {
+            return Err(Error::ResponseUnexpected);
+        }
+        let status: Self = content.take()?;
+        if !status.updated {
+            return Ok(None);
+        }
+        Ok(Some((status, Self::clear_request(output)?)))
+    }
+}
+
+impl<'de> Deserialize<'de> for TouchStatus {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (flag, event, x, y) = <(u8, TouchEvent, u16, u16)>::deserialize(deserializer)?;
+        Ok(Self {
+            updated: flag == Self::UPDATED,
+            event,
+            x,
+            y,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{request::Slice, response::word_data};
+
+    #[test]
+    fn release_request() {
+        let buf = &mut [0u8; 20];
+        let frame = TouchSimulation::release(0x0123, 0x0456)
+            .request(Slice::new(buf))
+            .unwrap()
+            .finalize(false)
+            .unwrap();
+        assert_eq!(
+            frame,
+            &[0x5A, 0xA5, 11, 0x82, 0x00, 0xD4, 0x5A, 0xA5, 0x00, 0x02, 0x01, 0x23, 0x04, 0x56]
+        );
+    }
+
+    #[test]
+    fn tap() {
+        let mut script = TouchScript::tap(10, 20);
+        assert_eq!(script.len(), 2);
+        assert_eq!(script.next(), Some(TouchSimulation::press(10, 20)));
+        assert_eq!(script.next(), Some(TouchSimulation::release(10, 20)));
+        assert_eq!(script.next(), None);
+    }
+
+    #[test]
+    fn long_press() {
+        let mut script = TouchScript::long_press(10, 20, 2);
+        assert_eq!(script.len(), 4);
+        assert_eq!(script.next(), Some(TouchSimulation::press(10, 20)));
+        assert_eq!(script.next(), Some(TouchSimulation::hold(10, 20)));
+        assert_eq!(script.next(), Some(TouchSimulation::hold(10, 20)));
+        assert_eq!(script.next(), Some(TouchSimulation::release(10, 20)));
+        assert_eq!(script.next(), None);
+    }
+
+    #[test]
+    fn drag() {
+        let mut script = TouchScript::drag((100, 0), (0, 300), 2);
+        assert_eq!(script.next(), Some(TouchSimulation::press(100, 0)));
+        assert_eq!(script.next(), Some(TouchSimulation::hold(67, 100)));
+        assert_eq!(script.next(), Some(TouchSimulation::hold(34, 200)));
+        assert_eq!(script.next(), Some(TouchSimulation::release(0, 300)));
+        assert_eq!(script.next(), None);
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn frames() {
+        use crate::request::HVec;
+        let frames: heapless::Vec<_, 2> = TouchScript::tap(1, 2)
+            .frames::<HVec<16>>(true)
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(
+            &frames[0][..10],
+            &[0x5A, 0xA5, 13, 0x82, 0x00, 0xD4, 0x5A, 0xA5, 0x00, 0x01]
+        );
+        assert_eq!(
+            &frames[1][..10],
+            &[0x5A, 0xA5, 13, 0x82, 0x00, 0xD4, 0x5A, 0xA5, 0x00, 0x02]
+        );
+    }
+
+    #[test]
+    fn status() {
+        let input = [
+            0x5A, 0xA5, 10, 0x83, 0x00, 0x16, 3, 0x5A, 0x03, 0x01, 0x23, 0x04, 0x56,
+        ];
+        let (_, mut content) = word_data(&input);
+        assert_eq!(
+            TouchStatus {
+                updated: true,
+                event: TouchEvent::Hold,
+                x: 0x0123,
+                y: 0x0456,
+            },
+            content.take().unwrap()
+        );
+    }
+
+    #[test]
+    fn status_bad_event() {
+        let input = [
+            0x5A, 0xA5, 10, 0x83, 0x00, 0x16, 3, 0x5A, 0x04, 0x01, 0x23, 0x04, 0x56,
+        ];
+        let (_, mut content) = word_data(&input);
+        assert_eq!(Err(Error::SerdeDeCustom), content.take::<TouchStatus>());
+    }
+
+    #[test]
+    fn poll_not_updated() {
+        let input = [
+            0x5A, 0xA5, 10, 0x83, 0x00, 0x16, 3, 0x00, 0x02, 0x01, 0x23, 0x04, 0x56,
+        ];
+        let response = Response::from_bytes(&input, false).unwrap();
+        let buf = &mut [0u8; 16];
+        assert!(TouchStatus::poll(response, Slice::new(buf))
+            .unwrap()
+            .is_none());
+    }
+}